@@ -0,0 +1,818 @@
+// backend/src/adapters/cloud_mon.rs
+
+use chrono::{DateTime, FixedOffset, Utc};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use super::observability::Summary;
+use super::prometheus::{promql_str, PromClient};
+use super::sigv4::{self, Credentials};
+use super::{range_schema, time_bounds, Adapter, AdapterError, AdapterInfo, Params};
+use crate::router::Range;
+use crate::services::ServiceCatalog;
+use crate::util::now_gmt8;
+
+#[derive(Debug, Serialize)]
+pub struct CloudMon {
+    pub adapter: &'static str,
+    pub from: String,
+    pub to: String,
+    pub checked_at: String,
+    pub resources: Vec<Resource>,
+    /// Alarm/alert yang sedang aktif untuk resource di atas
+    pub alarms: Vec<Alarm>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Resource {
+    /// mis. payments-service@prod-cluster atau node-1:9100
+    pub name: String,
+    /// ecs_service | container | node
+    pub kind: &'static str,
+    pub cpu_pct: Summary,
+    pub memory_pct: Summary,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub running_tasks: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desired_tasks: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Alarm {
+    pub name: String,
+    pub state: String,
+    pub severity: Option<String>,
+    pub resource: Option<String>,
+    pub reason: Option<String>,
+    /// Sejak kapan state ini (GMT+8)
+    pub since: Option<String>,
+}
+
+fn gmt8() -> FixedOffset {
+    FixedOffset::east_opt(8 * 3600).expect("valid +08:00 offset")
+}
+
+/* ------------------------- Config ------------------------- */
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum Backend {
+    #[default]
+    Synthetic,
+    Cloudwatch(CloudwatchConfig),
+    Prometheus(PrometheusConfig),
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CloudwatchConfig {
+    pub region: String,
+    /// Override endpoint (LocalStack: http://localstack:4566); default
+    /// https://monitoring.<region>.amazonaws.com
+    pub endpoint_url: Option<String>,
+    /// Bisa lewat env AWS_ACCESS_KEY_ID / AWS_SECRET_ACCESS_KEY / AWS_SESSION_TOKEN
+    #[serde(default)]
+    pub access_key_id: String,
+    #[serde(default)]
+    pub secret_access_key: String,
+    #[serde(default)]
+    pub session_token: Option<String>,
+    /// Cluster ECS; ServiceName = container dari katalog service
+    pub cluster: String,
+    #[serde(default = "default_max_points")]
+    pub max_points: i64,
+}
+
+/// Template PromQL node_exporter/cAdvisor; placeholder `$selector`, `$window`
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PromQueries {
+    pub container_cpu: String,
+    pub container_memory: String,
+    pub container_tasks: String,
+    pub node_cpu: String,
+    pub node_memory: String,
+}
+
+impl Default for PromQueries {
+    fn default() -> Self {
+        Self {
+            container_cpu: "sum(rate(container_cpu_usage_seconds_total{$selector}[$window])) \
+                            / sum(container_spec_cpu_quota{$selector} / container_spec_cpu_period{$selector}) * 100"
+                .into(),
+            container_memory: "sum(container_memory_working_set_bytes{$selector}) \
+                               / sum(container_spec_memory_limit_bytes{$selector}) * 100"
+                .into(),
+            container_tasks: "count(container_memory_working_set_bytes{$selector})".into(),
+            node_cpu: "100 * (1 - avg by (instance) (rate(node_cpu_seconds_total{mode=\"idle\"}[$window])))"
+                .into(),
+            node_memory: "100 * (1 - node_memory_MemAvailable_bytes / node_memory_MemTotal_bytes)"
+                .into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PrometheusConfig {
+    pub base_url: String,
+    /// Bearer token; bisa lewat env PROMETHEUS_TOKEN
+    #[serde(default)]
+    pub token: String,
+    /// Label cAdvisor berisi nama container
+    #[serde(default = "default_container_label")]
+    pub container_label: String,
+    #[serde(default = "default_rate_window")]
+    pub rate_window: String,
+    #[serde(default = "default_max_points")]
+    pub max_points: i64,
+    #[serde(default)]
+    pub queries: PromQueries,
+}
+
+fn default_max_points() -> i64 {
+    120
+}
+fn default_container_label() -> String {
+    "container".into()
+}
+fn default_rate_window() -> String {
+    "5m".into()
+}
+
+fn check_url(url: &str, key: &str, errs: &mut Vec<String>) {
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        errs.push(format!(
+            "adapters.cloud_mon.{key} must start with http:// or https://"
+        ));
+    }
+}
+
+impl Backend {
+    pub fn validate(&self, errs: &mut Vec<String>) {
+        match self {
+            Backend::Synthetic => {}
+            Backend::Cloudwatch(c) => {
+                if let Some(u) = &c.endpoint_url {
+                    check_url(u, "endpoint_url", errs);
+                }
+                if c.region.trim().is_empty() || c.cluster.trim().is_empty() {
+                    errs.push("adapters.cloud_mon region and cluster must not be empty".into());
+                }
+                if c.max_points < 1 {
+                    errs.push("adapters.cloud_mon.max_points must be positive".into());
+                }
+            }
+            Backend::Prometheus(p) => {
+                check_url(&p.base_url, "base_url", errs);
+                if p.max_points < 1 {
+                    errs.push("adapters.cloud_mon.max_points must be positive".into());
+                }
+            }
+        }
+    }
+
+    pub fn build(&self, services: &ServiceCatalog) -> Box<dyn Adapter<Output = CloudMon>> {
+        match self {
+            Backend::Synthetic => Box::new(Synthetic {
+                services: services.clone(),
+            }),
+            Backend::Cloudwatch(c) => Box::new(Cloudwatch::new(c.clone(), services.clone())),
+            Backend::Prometheus(p) => Box::new(Prometheus::new(p.clone(), services.clone())),
+        }
+    }
+}
+
+fn params_schema() -> serde_json::Value {
+    range_schema(serde_json::json!({}))
+}
+
+/// Step dalam detik supaya titik per series ≤ max_points
+fn step_secs(from: DateTime<Utc>, to: DateTime<Utc>, max_points: i64, align: i64) -> i64 {
+    let span = (to - from).num_seconds().max(1);
+    let raw = (span + max_points - 1) / max_points;
+    ((raw + align - 1) / align).max(1) * align
+}
+
+/* ------------------------- Synthetic ------------------------- */
+
+/// Satu service ECS contoh tanpa alarm
+pub struct Synthetic {
+    services: ServiceCatalog,
+}
+
+impl Adapter for Synthetic {
+    type Output = CloudMon;
+
+    fn describe(&self) -> AdapterInfo {
+        AdapterInfo {
+            domain: "cloud_mon",
+            endpoint: "/api/cloud-mon",
+            backend: "synthetic",
+            description: "Resource cloud (CPU/memori/task) dan alarm aktif",
+        }
+    }
+
+    fn params_schema(&self) -> serde_json::Value {
+        params_schema()
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        q: &'a Range,
+        _params: &'a Params,
+    ) -> BoxFuture<'a, Result<CloudMon, AdapterError>> {
+        Box::pin(async move {
+            let (from, to) = time_bounds(q)?;
+            let container = match &q.service {
+                Some(s) => self.services.container(s),
+                None => "payment-service".into(),
+            };
+            Ok(CloudMon {
+                adapter: "cloud_monitoring",
+                from: from.to_rfc3339(),
+                to: to.to_rfc3339(),
+                checked_at: now_gmt8().to_rfc3339(),
+                resources: vec![Resource {
+                    name: format!("{container}@ecs"),
+                    kind: "ecs_service",
+                    cpu_pct: Summary::of(&[31.0, 42.5, 38.0]),
+                    memory_pct: Summary::of(&[55.0, 57.5, 56.0]),
+                    running_tasks: Some(3),
+                    desired_tasks: Some(3),
+                }],
+                alarms: vec![],
+            })
+        })
+    }
+}
+
+/* ------------------------- CloudWatch ------------------------- */
+
+/// Backend CloudWatch (protokol JSON, SigV4): metrik AWS/ECS + Container Insights
+/// dan DescribeAlarms. Bisa diarahkan ke LocalStack lewat endpoint_url.
+pub struct Cloudwatch {
+    cfg: CloudwatchConfig,
+    endpoint: String,
+    services: ServiceCatalog,
+    client: reqwest::Client,
+}
+
+impl Cloudwatch {
+    pub fn new(cfg: CloudwatchConfig, services: ServiceCatalog) -> Self {
+        let endpoint = cfg
+            .endpoint_url
+            .clone()
+            .unwrap_or_else(|| format!("https://monitoring.{}.amazonaws.com", cfg.region));
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(20))
+            .build()
+            .unwrap_or_default();
+        Self {
+            cfg,
+            endpoint,
+            services,
+            client,
+        }
+    }
+
+    async fn call(
+        &self,
+        action: &str,
+        body: &serde_json::Value,
+    ) -> Result<serde_json::Value, AdapterError> {
+        let url = reqwest::Url::parse(&self.endpoint)
+            .map_err(|e| AdapterError::Upstream(format!("cloudwatch endpoint: {e}")))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(h), Some(p)) => format!("{h}:{p}"),
+            (Some(h), None) => h.to_string(),
+            _ => {
+                return Err(AdapterError::Upstream(
+                    "cloudwatch endpoint has no host".into(),
+                ))
+            }
+        };
+        let payload = body.to_string().into_bytes();
+        let mut headers = vec![
+            (
+                "content-type".to_string(),
+                "application/x-amz-json-1.0".to_string(),
+            ),
+            ("host".to_string(), host),
+            (
+                "x-amz-target".to_string(),
+                format!("GraniteServiceVersion20100801.{action}"),
+            ),
+        ];
+        let creds = Credentials {
+            access_key_id: self.cfg.access_key_id.clone(),
+            secret_access_key: self.cfg.secret_access_key.clone(),
+            session_token: self.cfg.session_token.clone(),
+        };
+        sigv4::sign(
+            &creds,
+            &self.cfg.region,
+            "monitoring",
+            "POST",
+            &url,
+            &mut headers,
+            &payload,
+            Utc::now(),
+        );
+
+        let mut req = self.client.post(url).body(payload);
+        for (k, v) in headers.iter().filter(|(k, _)| k != "host") {
+            req = req.header(k, v);
+        }
+        let resp = req
+            .send()
+            .await
+            .map_err(|e| AdapterError::Upstream(format!("cloudwatch {action}: {e}")))?;
+        let status = resp.status();
+        let json: serde_json::Value = resp.json().await.unwrap_or_default();
+        if !status.is_success() {
+            let msg = json["message"]
+                .as_str()
+                .or(json["Message"].as_str())
+                .unwrap_or_default();
+            let kind = json["__type"].as_str().unwrap_or_default();
+            return Err(
+                if status == reqwest::StatusCode::BAD_REQUEST && !kind.contains("Signature") {
+                    AdapterError::BadParams(format!("cloudwatch {action}: {kind} {msg}"))
+                } else {
+                    AdapterError::Upstream(format!("cloudwatch {action} → {status}: {kind} {msg}"))
+                },
+            );
+        }
+        Ok(json)
+    }
+
+    /// GetMetricData (semua halaman) → Id → nilai
+    async fn metric_data(
+        &self,
+        queries: serde_json::Value,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<BTreeMap<String, Vec<f64>>, AdapterError> {
+        let mut out: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+        let mut next: Option<String> = None;
+        // cukup beberapa halaman; max_points sudah membatasi jumlah titik
+        for _ in 0..5 {
+            let mut body = serde_json::json!({
+                "StartTime": from.timestamp(),
+                "EndTime": to.timestamp(),
+                "ScanBy": "TimestampAscending",
+                "MetricDataQueries": queries,
+            });
+            if let Some(t) = &next {
+                body["NextToken"] = t.clone().into();
+            }
+            let resp = self.call("GetMetricData", &body).await?;
+            for r in resp["MetricDataResults"].as_array().into_iter().flatten() {
+                let id = r["Id"].as_str().unwrap_or_default().to_string();
+                let values = r["Values"].as_array().into_iter().flatten();
+                out.entry(id)
+                    .or_default()
+                    .extend(values.filter_map(|v| v.as_f64()));
+            }
+            next = resp["NextToken"].as_str().map(str::to_string);
+            if next.is_none() {
+                break;
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl Adapter for Cloudwatch {
+    type Output = CloudMon;
+
+    fn describe(&self) -> AdapterInfo {
+        AdapterInfo {
+            domain: "cloud_mon",
+            endpoint: "/api/cloud-mon",
+            backend: "cloudwatch",
+            description: "CPU/memori/task service ECS dan alarm aktif dari CloudWatch",
+        }
+    }
+
+    fn params_schema(&self) -> serde_json::Value {
+        params_schema()
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        q: &'a Range,
+        _params: &'a Params,
+    ) -> BoxFuture<'a, Result<CloudMon, AdapterError>> {
+        Box::pin(async move {
+            let service = q
+                .service
+                .clone()
+                .filter(|s| !s.trim().is_empty())
+                .ok_or_else(|| AdapterError::BadParams("service is required".into()))?;
+            let (from, to) = time_bounds(q)?;
+            let container = self.services.container(&service);
+            let cluster = &self.cfg.cluster;
+            let period = step_secs(from, to, self.cfg.max_points, 60);
+
+            let dims = serde_json::json!([
+                { "Name": "ClusterName", "Value": cluster },
+                { "Name": "ServiceName", "Value": container }
+            ]);
+            let stat = |id: &str, ns: &str, metric: &str, stat: &str| {
+                serde_json::json!({
+                    "Id": id,
+                    "ReturnData": true,
+                    "MetricStat": {
+                        "Metric": { "Namespace": ns, "MetricName": metric, "Dimensions": dims },
+                        "Period": period,
+                        "Stat": stat
+                    }
+                })
+            };
+            let queries = serde_json::json!([
+                stat("cpu", "AWS/ECS", "CPUUtilization", "Average"),
+                stat("mem", "AWS/ECS", "MemoryUtilization", "Average"),
+                stat(
+                    "running",
+                    "ECS/ContainerInsights",
+                    "RunningTaskCount",
+                    "Average"
+                ),
+                stat(
+                    "desired",
+                    "ECS/ContainerInsights",
+                    "DesiredTaskCount",
+                    "Average"
+                ),
+            ]);
+            let data = self.metric_data(queries, from, to).await?;
+            let last = |id: &str| {
+                data.get(id)
+                    .and_then(|v| v.last())
+                    .map(|v| v.round() as i64)
+            };
+
+            let alarms = self
+                .call(
+                    "DescribeAlarms",
+                    &serde_json::json!({ "StateValue": "ALARM", "AlarmTypes": ["MetricAlarm"] }),
+                )
+                .await?;
+            let alarms = alarms["MetricAlarms"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter(|a| {
+                    let dim = |name: &str| {
+                        a["Dimensions"]
+                            .as_array()
+                            .into_iter()
+                            .flatten()
+                            .find(|d| d["Name"] == name)
+                            .and_then(|d| d["Value"].as_str())
+                    };
+                    // alarm service ini, atau alarm level cluster
+                    match dim("ServiceName") {
+                        Some(s) => s == container,
+                        None => dim("ClusterName") == Some(cluster.as_str()),
+                    }
+                })
+                .map(|a| Alarm {
+                    name: a["AlarmName"].as_str().unwrap_or_default().to_string(),
+                    state: a["StateValue"].as_str().unwrap_or("ALARM").to_string(),
+                    severity: None,
+                    resource: a["MetricName"]
+                        .as_str()
+                        .map(|m| format!("{container}@{cluster}:{m}")),
+                    reason: a["StateReason"].as_str().map(str::to_string),
+                    since: a["StateUpdatedTimestamp"]
+                        .as_f64()
+                        .and_then(|t| DateTime::from_timestamp(t as i64, 0))
+                        .map(|t| t.with_timezone(&gmt8()).to_rfc3339()),
+                })
+                .collect();
+
+            let summary = |id: &str| Summary::of(data.get(id).map(Vec::as_slice).unwrap_or(&[]));
+            Ok(CloudMon {
+                adapter: "cloud_monitoring",
+                from: from.to_rfc3339(),
+                to: to.to_rfc3339(),
+                checked_at: now_gmt8().to_rfc3339(),
+                resources: vec![Resource {
+                    name: format!("{container}@{cluster}"),
+                    kind: "ecs_service",
+                    cpu_pct: summary("cpu"),
+                    memory_pct: summary("mem"),
+                    running_tasks: last("running"),
+                    desired_tasks: last("desired"),
+                }],
+                alarms,
+            })
+        })
+    }
+}
+
+/* ------------------------- Prometheus ------------------------- */
+
+/// Backend Prometheus on-prem: cAdvisor per container (bila ada service) atau
+/// node_exporter per node, plus ALERTS yang sedang firing
+pub struct Prometheus {
+    cfg: PrometheusConfig,
+    services: ServiceCatalog,
+    client: PromClient,
+}
+
+impl Prometheus {
+    pub fn new(cfg: PrometheusConfig, services: ServiceCatalog) -> Self {
+        let client = PromClient::new(&cfg.base_url, &cfg.token);
+        Self {
+            cfg,
+            services,
+            client,
+        }
+    }
+
+    fn render(&self, template: &str, selector: &str) -> String {
+        template
+            .replace("$selector", selector)
+            .replace("$window", &self.cfg.rate_window)
+    }
+
+    /// Gabung titik semua series per nilai label `by` (kosong = satu grup)
+    async fn grouped(
+        &self,
+        query: &str,
+        by: &str,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        step: i64,
+    ) -> Result<BTreeMap<String, Vec<f64>>, AdapterError> {
+        let mut out: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+        for s in self.client.query_range(query, from, to, step).await? {
+            let key = s.labels.get(by).cloned().unwrap_or_default();
+            out.entry(key)
+                .or_default()
+                .extend(s.points.into_iter().map(|p| p.1));
+        }
+        Ok(out)
+    }
+}
+
+impl Adapter for Prometheus {
+    type Output = CloudMon;
+
+    fn describe(&self) -> AdapterInfo {
+        AdapterInfo {
+            domain: "cloud_mon",
+            endpoint: "/api/cloud-mon",
+            backend: "prometheus",
+            description: "CPU/memori container (cAdvisor) atau node (node_exporter) dan alert firing dari Prometheus",
+        }
+    }
+
+    fn params_schema(&self) -> serde_json::Value {
+        params_schema()
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        q: &'a Range,
+        _params: &'a Params,
+    ) -> BoxFuture<'a, Result<CloudMon, AdapterError>> {
+        Box::pin(async move {
+            let (from, to) = time_bounds(q)?;
+            let step = step_secs(from, to, self.cfg.max_points, 15);
+            let qs = &self.cfg.queries;
+            let service = q.service.clone().filter(|s| !s.trim().is_empty());
+
+            let resources = match &service {
+                Some(s) => {
+                    let container = self.services.container(s);
+                    let selector =
+                        format!("{}={}", self.cfg.container_label, promql_str(&container));
+                    let cpu = self
+                        .grouped(
+                            &self.render(&qs.container_cpu, &selector),
+                            "",
+                            from,
+                            to,
+                            step,
+                        )
+                        .await?;
+                    let mem = self
+                        .grouped(
+                            &self.render(&qs.container_memory, &selector),
+                            "",
+                            from,
+                            to,
+                            step,
+                        )
+                        .await?;
+                    let tasks = self
+                        .client
+                        .query(&self.render(&qs.container_tasks, &selector), to)
+                        .await?;
+                    let values = |m: &BTreeMap<String, Vec<f64>>| {
+                        Summary::of(m.values().next().map(Vec::as_slice).unwrap_or(&[]))
+                    };
+                    vec![Resource {
+                        name: container,
+                        kind: "container",
+                        cpu_pct: values(&cpu),
+                        memory_pct: values(&mem),
+                        running_tasks: tasks
+                            .first()
+                            .and_then(|s| s.points.last())
+                            .map(|p| p.1.round() as i64)
+                            .or(Some(0)),
+                        desired_tasks: None,
+                    }]
+                }
+                None => {
+                    let cpu = self
+                        .grouped(&self.render(&qs.node_cpu, ""), "instance", from, to, step)
+                        .await?;
+                    let mem = self
+                        .grouped(
+                            &self.render(&qs.node_memory, ""),
+                            "instance",
+                            from,
+                            to,
+                            step,
+                        )
+                        .await?;
+                    let nodes: std::collections::BTreeSet<&String> =
+                        cpu.keys().chain(mem.keys()).collect();
+                    nodes
+                        .into_iter()
+                        .map(|n| Resource {
+                            name: n.clone(),
+                            kind: "node",
+                            cpu_pct: Summary::of(cpu.get(n).map(Vec::as_slice).unwrap_or(&[])),
+                            memory_pct: Summary::of(mem.get(n).map(Vec::as_slice).unwrap_or(&[])),
+                            running_tasks: None,
+                            desired_tasks: None,
+                        })
+                        .collect()
+                }
+            };
+
+            // alert firing; bila ada service, hanya yang label-nya menyebut service itu
+            let wanted: Vec<String> = match &service {
+                Some(s) => {
+                    let mut w = vec![s.clone(), self.services.container(s)];
+                    w.extend(self.services.lookup(s).map(|(n, _)| n.to_string()));
+                    w
+                }
+                None => vec![],
+            };
+            let alarms = self
+                .client
+                .query("ALERTS{alertstate=\"firing\"}", to)
+                .await?
+                .into_iter()
+                .filter(|a| wanted.is_empty() || a.labels.values().any(|v| wanted.contains(v)))
+                .map(|a| Alarm {
+                    name: a.labels.get("alertname").cloned().unwrap_or_default(),
+                    state: "firing".into(),
+                    severity: a.labels.get("severity").cloned(),
+                    resource: a
+                        .labels
+                        .get(&self.cfg.container_label)
+                        .or(a.labels.get("instance"))
+                        .cloned(),
+                    reason: None,
+                    since: None,
+                })
+                .collect();
+
+            Ok(CloudMon {
+                adapter: "cloud_monitoring",
+                from: from.to_rfc3339(),
+                to: to.to_rfc3339(),
+                checked_at: now_gmt8().to_rfc3339(),
+                resources,
+                alarms,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, header_exists, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn range(service: Option<&str>) -> Range {
+        serde_json::from_value(serde_json::json!({
+            "service": service,
+            "date_from": "2025-10-09T00:00:00Z", "date_to": "2025-10-09T01:00:00Z"
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cloudwatch_metrics_and_alarms() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header(
+                "x-amz-target",
+                "GraniteServiceVersion20100801.GetMetricData",
+            ))
+            .and(header_exists("authorization"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "MetricDataResults": [
+                    { "Id": "cpu", "Values": [20.0, 60.0], "StatusCode": "Complete" },
+                    { "Id": "mem", "Values": [50.0], "StatusCode": "Complete" },
+                    { "Id": "running", "Values": [2.0], "StatusCode": "Complete" },
+                    { "Id": "desired", "Values": [3.0], "StatusCode": "Complete" }
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(header("x-amz-target", "GraniteServiceVersion20100801.DescribeAlarms"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "MetricAlarms": [
+                    { "AlarmName": "payments-cpu-high", "StateValue": "ALARM", "MetricName": "CPUUtilization",
+                      "StateReason": "Threshold Crossed", "StateUpdatedTimestamp": 1759968000.0,
+                      "Dimensions": [{ "Name": "ClusterName", "Value": "prod" },
+                                     { "Name": "ServiceName", "Value": "payments-service" }] },
+                    { "AlarmName": "orders-mem-high", "StateValue": "ALARM",
+                      "Dimensions": [{ "Name": "ServiceName", "Value": "orders-service" }] }
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let cfg: Backend = toml::from_str(&format!(
+            "backend = \"cloudwatch\"\nregion = \"ap-southeast-1\"\ncluster = \"prod\"\n\
+             endpoint_url = \"{}\"\naccess_key_id = \"test\"\nsecret_access_key = \"test\"",
+            server.uri()
+        ))
+        .unwrap();
+        let out = cfg
+            .build(&ServiceCatalog::default())
+            .fetch(&range(Some("payment")), &Params::new())
+            .await
+            .unwrap();
+        let r = &out.resources[0];
+        assert_eq!(r.name, "payments-service@prod");
+        assert_eq!((r.cpu_pct.avg, r.cpu_pct.max), (Some(40.0), Some(60.0)));
+        assert_eq!((r.running_tasks, r.desired_tasks), (Some(2), Some(3)));
+        assert_eq!(out.alarms.len(), 1);
+        assert_eq!(
+            out.alarms[0].since.as_deref(),
+            Some("2025-10-09T08:00:00+08:00")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prometheus_nodes_and_firing_alerts() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/query_range"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "status": "success",
+                "data": { "resultType": "matrix", "result": [
+                    { "metric": { "instance": "node-1:9100" }, "values": [[1759968000, "10"], [1759968060, "30"]] },
+                    { "metric": { "instance": "node-2:9100" }, "values": [[1759968000, "70"]] }
+                ]}
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/query"))
+            .and(query_param("query", "ALERTS{alertstate=\"firing\"}"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "status": "success",
+                "data": { "resultType": "vector", "result": [
+                    { "metric": { "alertname": "NodeDiskFull", "severity": "critical", "instance": "node-2:9100" },
+                      "value": [1759971600, "1"] }
+                ]}
+            })))
+            .mount(&server)
+            .await;
+
+        let cfg: Backend = toml::from_str(&format!(
+            "backend = \"prometheus\"\nbase_url = \"{}\"",
+            server.uri()
+        ))
+        .unwrap();
+        let out = cfg
+            .build(&ServiceCatalog::default())
+            .fetch(&range(None), &Params::new())
+            .await
+            .unwrap();
+        let names: Vec<&str> = out.resources.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["node-1:9100", "node-2:9100"]);
+        assert_eq!(out.resources[0].cpu_pct.avg, Some(20.0));
+        assert_eq!(out.alarms[0].severity.as_deref(), Some("critical"));
+        assert_eq!(out.alarms[0].resource.as_deref(), Some("node-2:9100"));
+    }
+}
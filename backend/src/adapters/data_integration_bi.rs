@@ -0,0 +1,48 @@
+// backend/src/adapters/data_integration_bi.rs
+
+use futures_util::future::BoxFuture;
+use serde::Serialize;
+
+use super::{range_schema, Adapter, AdapterError, AdapterInfo, Params};
+use crate::router::Range;
+use crate::util::now_gmt8;
+
+#[derive(Debug, Serialize)]
+pub struct DataIntegration {
+    pub adapter: &'static str,
+    pub pipeline: String,
+    pub checked_at: String,
+}
+
+pub struct Synthetic;
+
+impl Adapter for Synthetic {
+    type Output = DataIntegration;
+
+    fn describe(&self) -> AdapterInfo {
+        AdapterInfo {
+            domain: "data_integration_bi",
+            endpoint: "/api/data-integration-bi",
+            backend: "synthetic",
+            description: "Status job ETL / integrasi data ke BI",
+        }
+    }
+
+    fn params_schema(&self) -> serde_json::Value {
+        range_schema(serde_json::json!({}))
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        _range: &'a Range,
+        _params: &'a Params,
+    ) -> BoxFuture<'a, Result<DataIntegration, AdapterError>> {
+        Box::pin(async move {
+            Ok(DataIntegration {
+                adapter: "data_integration_bi",
+                pipeline: "etl_ridership_to_bi".into(),
+                checked_at: now_gmt8().to_rfc3339(),
+            })
+        })
+    }
+}
@@ -0,0 +1,617 @@
+// backend/src/adapters/gitlab_ci.rs
+
+use futures_util::future::BoxFuture;
+use regex::Regex;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use super::{range_schema, time_bounds, upstream_body, Adapter, AdapterError, AdapterInfo, Params};
+use crate::router::Range;
+use crate::util::now_gmt8;
+
+#[derive(Debug, Serialize)]
+pub struct GitlabCi {
+    pub adapter: &'static str,
+    pub project: String,
+    pub branch: String,
+    /// Status pipeline terbaru (success | failed | running | ... | no_pipelines)
+    pub status: String,
+    pub checked_at: String,
+    /// Test gagal di pipeline gagal terbaru ("suite: class.name")
+    pub failed_tests: Vec<String>,
+    pub pipelines: Vec<Pipeline>,
+    pub failed_jobs: Vec<FailedJob>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pipeline {
+    pub id: u64,
+    pub status: String,
+    #[serde(default)]
+    pub sha: String,
+    #[serde(default)]
+    pub web_url: String,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FailedJob {
+    pub pipeline_id: u64,
+    pub id: u64,
+    pub name: String,
+    pub stage: String,
+    pub failure_reason: Option<String>,
+    pub web_url: String,
+    /// Ekor trace yang sudah dibersihkan dari ANSI/section marker
+    pub trace_excerpt: String,
+}
+
+/* ------------------------- Config ------------------------- */
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum Backend {
+    #[default]
+    Synthetic,
+    Gitlab(GitlabConfig),
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GitlabConfig {
+    /// mis. https://gitlab.example.com (tanpa /api/v4)
+    pub base_url: String,
+    /// Personal/project access token (read_api); bisa lewat env GITLAB_TOKEN
+    #[serde(default)]
+    pub token: String,
+    /// Path project (group/name) atau id numerik
+    pub default_project: String,
+    #[serde(default = "default_branch")]
+    pub default_branch: String,
+    #[serde(default = "default_max_pipelines")]
+    pub max_pipelines: usize,
+    #[serde(default = "default_max_failed_jobs")]
+    pub max_failed_jobs: usize,
+    #[serde(default = "default_trace_tail_lines")]
+    pub trace_tail_lines: usize,
+}
+
+fn default_branch() -> String {
+    "main".into()
+}
+fn default_max_pipelines() -> usize {
+    20
+}
+fn default_max_failed_jobs() -> usize {
+    10
+}
+fn default_trace_tail_lines() -> usize {
+    30
+}
+
+impl Backend {
+    pub fn validate(&self, errs: &mut Vec<String>) {
+        if let Backend::Gitlab(g) = self {
+            if !(g.base_url.starts_with("http://") || g.base_url.starts_with("https://")) {
+                errs.push("adapters.gitlab_ci.base_url must start with http:// or https://".into());
+            }
+            if g.default_project.trim().is_empty() {
+                errs.push("adapters.gitlab_ci.default_project must not be empty".into());
+            }
+            if g.max_pipelines == 0 || g.trace_tail_lines == 0 {
+                errs.push("adapters.gitlab_ci limits must be positive".into());
+            }
+        }
+    }
+
+    pub fn build(&self) -> Box<dyn Adapter<Output = GitlabCi>> {
+        match self {
+            Backend::Synthetic => Box::new(Synthetic),
+            Backend::Gitlab(g) => Box::new(Gitlab::new(g.clone())),
+        }
+    }
+}
+
+fn params_schema() -> serde_json::Value {
+    range_schema(serde_json::json!({
+        "project": { "type": "string", "description": "path project GitLab (group/name) atau id" },
+        "branch":  { "type": "string", "description": "ref/branch pipeline" }
+    }))
+}
+
+/* ------------------------- Synthetic ------------------------- */
+
+/// Data contoh: pipeline terakhir di main sukses
+pub struct Synthetic;
+
+impl Adapter for Synthetic {
+    type Output = GitlabCi;
+
+    fn describe(&self) -> AdapterInfo {
+        AdapterInfo {
+            domain: "gitlab_ci",
+            endpoint: "/api/gitlab-ci",
+            backend: "synthetic",
+            description: "Status pipeline CI/CD terakhir dan test yang gagal",
+        }
+    }
+
+    fn params_schema(&self) -> serde_json::Value {
+        params_schema()
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        _range: &'a Range,
+        params: &'a Params,
+    ) -> BoxFuture<'a, Result<GitlabCi, AdapterError>> {
+        Box::pin(async move {
+            let now = now_gmt8().to_rfc3339();
+            Ok(GitlabCi {
+                adapter: "gitlab_ci",
+                project: params
+                    .get("project")
+                    .cloned()
+                    .unwrap_or_else(|| "ticketing-backend".into()),
+                branch: params
+                    .get("branch")
+                    .cloned()
+                    .unwrap_or_else(|| "main".into()),
+                status: "success".into(), // dummy: CI terakhir sukses
+                checked_at: now.clone(),
+                failed_tests: vec![], // kosong kalau sukses
+                pipelines: vec![Pipeline {
+                    id: 1,
+                    status: "success".into(),
+                    sha: "0000000".into(),
+                    web_url: String::new(),
+                    created_at: Some(now.clone()),
+                    updated_at: Some(now),
+                }],
+                failed_jobs: vec![],
+            })
+        })
+    }
+}
+
+/* ------------------------- GitLab REST ------------------------- */
+
+#[derive(Debug, Deserialize)]
+struct ApiJob {
+    id: u64,
+    name: String,
+    #[serde(default)]
+    stage: String,
+    failure_reason: Option<String>,
+    #[serde(default)]
+    web_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiTestReport {
+    #[serde(default)]
+    test_suites: Vec<ApiTestSuite>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiTestSuite {
+    name: String,
+    #[serde(default)]
+    test_cases: Vec<ApiTestCase>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiTestCase {
+    name: String,
+    classname: Option<String>,
+    status: String,
+}
+
+/// Backend GitLab REST API v4 (pipelines, jobs, trace, test report)
+pub struct Gitlab {
+    cfg: GitlabConfig,
+    client: reqwest::Client,
+}
+
+/// Ukuran halaman maksimum yang diterima GitLab
+const PER_PAGE_MAX: usize = 100;
+
+impl Gitlab {
+    pub fn new(cfg: GitlabConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(20))
+            .build()
+            .unwrap_or_default();
+        Self { cfg, client }
+    }
+
+    /// URL /api/v4/projects/{project}/...; path project di-encode jadi satu segmen
+    fn url(&self, project: &str, rest: &[&str]) -> Result<reqwest::Url, AdapterError> {
+        let mut url = reqwest::Url::parse(&self.cfg.base_url)
+            .map_err(|e| AdapterError::Upstream(format!("invalid gitlab base_url: {e}")))?;
+        url.path_segments_mut()
+            .map_err(|_| AdapterError::Upstream("gitlab base_url cannot be a base".into()))?
+            .pop_if_empty()
+            .extend(["api", "v4", "projects", project])
+            .extend(rest);
+        Ok(url)
+    }
+
+    async fn get(
+        &self,
+        url: reqwest::Url,
+        query: &[(&str, String)],
+    ) -> Result<reqwest::Response, AdapterError> {
+        let mut req = self.client.get(url.clone()).query(query);
+        if !self.cfg.token.is_empty() {
+            req = req.header("PRIVATE-TOKEN", &self.cfg.token);
+        }
+        let resp = req
+            .send()
+            .await
+            .map_err(|e| AdapterError::Upstream(format!("gitlab {}: {e}", url.path())))?;
+        let status = resp.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(AdapterError::BadParams(format!(
+                "gitlab {} not found (unknown project or no access)",
+                url.path()
+            )));
+        }
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            return Err(AdapterError::Upstream(format!(
+                "gitlab {} → {status}: {}",
+                url.path(),
+                upstream_body(&body)
+            )));
+        }
+        Ok(resp)
+    }
+
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        url: reqwest::Url,
+        query: &[(&str, String)],
+    ) -> Result<(T, Option<u32>), AdapterError> {
+        let resp = self.get(url, query).await?;
+        let next = resp
+            .headers()
+            .get("x-next-page")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok());
+        let body = resp
+            .json::<T>()
+            .await
+            .map_err(|e| AdapterError::Upstream(format!("gitlab: unexpected response: {e}")))?;
+        Ok((body, next))
+    }
+
+    /// Pipeline di range (terbaru dulu), mengikuti X-Next-Page sampai max_pipelines
+    async fn pipelines(
+        &self,
+        project: &str,
+        branch: &str,
+        range: &Range,
+    ) -> Result<Vec<Pipeline>, AdapterError> {
+        let (from, to) = time_bounds(range)?;
+        let url = self.url(project, &["pipelines"])?;
+        let per_page = self.cfg.max_pipelines.min(PER_PAGE_MAX);
+        let mut out = Vec::new();
+        let mut page = 1;
+        loop {
+            let query = [
+                ("ref", branch.to_string()),
+                ("updated_after", from.to_rfc3339()),
+                ("updated_before", to.to_rfc3339()),
+                ("order_by", "id".to_string()),
+                ("sort", "desc".to_string()),
+                ("per_page", per_page.to_string()),
+                ("page", page.to_string()),
+            ];
+            let (batch, next): (Vec<Pipeline>, _) = self.get_json(url.clone(), &query).await?;
+            out.extend(batch);
+            match next {
+                Some(n) if out.len() < self.cfg.max_pipelines => page = n,
+                _ => break,
+            }
+        }
+        out.truncate(self.cfg.max_pipelines);
+        Ok(out)
+    }
+
+    async fn failed_jobs(
+        &self,
+        project: &str,
+        pipeline_id: u64,
+    ) -> Result<Vec<ApiJob>, AdapterError> {
+        let url = self.url(project, &["pipelines", &pipeline_id.to_string(), "jobs"])?;
+        let query = [
+            ("scope[]", "failed".to_string()),
+            ("per_page", PER_PAGE_MAX.to_string()),
+        ];
+        let (jobs, _) = self.get_json(url, &query).await?;
+        Ok(jobs)
+    }
+
+    async fn trace(&self, project: &str, job_id: u64) -> String {
+        let res = async {
+            let url = self.url(project, &["jobs", &job_id.to_string(), "trace"])?;
+            let resp = self.get(url, &[]).await?;
+            resp.text()
+                .await
+                .map_err(|e| AdapterError::Upstream(e.to_string()))
+        }
+        .await;
+        match res {
+            Ok(raw) => trace_excerpt(&raw, self.cfg.trace_tail_lines),
+            Err(e) => format!("(trace unavailable: {e})"),
+        }
+    }
+
+    /// Test report tidak ada di GitLab lama / pipeline tanpa artifact junit → kosong
+    async fn failed_tests(&self, project: &str, pipeline_id: u64) -> Vec<String> {
+        let url = match self.url(
+            project,
+            &["pipelines", &pipeline_id.to_string(), "test_report"],
+        ) {
+            Ok(u) => u,
+            Err(_) => return vec![],
+        };
+        let report: ApiTestReport = match self.get_json(url, &[]).await {
+            Ok((r, _)) => r,
+            Err(e) => {
+                tracing::debug!(pipeline_id, "no test report: {e}");
+                return vec![];
+            }
+        };
+        report
+            .test_suites
+            .into_iter()
+            .flat_map(|s| {
+                s.test_cases
+                    .into_iter()
+                    .filter(|c| matches!(c.status.as_str(), "failed" | "error"))
+                    .map(move |c| match c.classname {
+                        Some(cls) if !cls.is_empty() => format!("{}: {cls}.{}", s.name, c.name),
+                        _ => format!("{}: {}", s.name, c.name),
+                    })
+            })
+            .collect()
+    }
+}
+
+impl Adapter for Gitlab {
+    type Output = GitlabCi;
+
+    fn describe(&self) -> AdapterInfo {
+        AdapterInfo {
+            domain: "gitlab_ci",
+            endpoint: "/api/gitlab-ci",
+            backend: "gitlab",
+            description: "Pipeline GitLab di range: status, job gagal + potongan trace, test gagal",
+        }
+    }
+
+    fn params_schema(&self) -> serde_json::Value {
+        params_schema()
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        range: &'a Range,
+        params: &'a Params,
+    ) -> BoxFuture<'a, Result<GitlabCi, AdapterError>> {
+        Box::pin(async move {
+            let project = params
+                .get("project")
+                .filter(|p| !p.trim().is_empty())
+                .unwrap_or(&self.cfg.default_project)
+                .clone();
+            let branch = params
+                .get("branch")
+                .filter(|b| !b.trim().is_empty())
+                .unwrap_or(&self.cfg.default_branch)
+                .clone();
+
+            let pipelines = self.pipelines(&project, &branch, range).await?;
+
+            let mut failed_jobs = Vec::new();
+            for p in pipelines.iter().filter(|p| p.status == "failed") {
+                if failed_jobs.len() >= self.cfg.max_failed_jobs {
+                    break;
+                }
+                for job in self.failed_jobs(&project, p.id).await? {
+                    if failed_jobs.len() >= self.cfg.max_failed_jobs {
+                        break;
+                    }
+                    let trace_excerpt = self.trace(&project, job.id).await;
+                    failed_jobs.push(FailedJob {
+                        pipeline_id: p.id,
+                        id: job.id,
+                        name: job.name,
+                        stage: job.stage,
+                        failure_reason: job.failure_reason,
+                        web_url: job.web_url,
+                        trace_excerpt,
+                    });
+                }
+            }
+
+            let failed_tests = match pipelines.iter().find(|p| p.status == "failed") {
+                Some(p) => self.failed_tests(&project, p.id).await,
+                None => vec![],
+            };
+
+            Ok(GitlabCi {
+                adapter: "gitlab_ci",
+                status: pipelines
+                    .first()
+                    .map(|p| p.status.clone())
+                    .unwrap_or_else(|| "no_pipelines".into()),
+                project,
+                branch,
+                checked_at: now_gmt8().to_rfc3339(),
+                failed_tests,
+                pipelines,
+                failed_jobs,
+            })
+        })
+    }
+}
+
+/// Baris terakhir trace tanpa escape ANSI & marker section GitLab, tiap baris dipotong
+pub fn trace_excerpt(raw: &str, tail_lines: usize) -> String {
+    const MAX_LINE: usize = 300;
+    static NOISE: OnceLock<Regex> = OnceLock::new();
+    let noise = NOISE.get_or_init(|| {
+        Regex::new(r"\x1b\[[0-9;]*[A-Za-z]|section_(start|end):\d+:[A-Za-z0-9_\-\[\]=,]*")
+            .expect("valid trace regex")
+    });
+
+    let lines: Vec<String> = raw
+        .lines()
+        .map(|l| {
+            // progress bar menimpa baris dengan \r; ambil tampilan terakhir
+            let l = l.rsplit('\r').find(|s| !s.trim().is_empty()).unwrap_or("");
+            let clean = noise.replace_all(l, "");
+            let clean = clean.trim_end();
+            match clean.char_indices().nth(MAX_LINE) {
+                Some((i, _)) => format!("{}…", &clean[..i]),
+                None => clean.to_string(),
+            }
+        })
+        .filter(|l| !l.trim().is_empty())
+        .collect();
+    lines[lines.len().saturating_sub(tail_lines)..].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_trace_excerpt_strips_noise_and_keeps_tail() {
+        let raw = "section_start:1700000000:step_script\r\x1b[0K\x1b[32;1m$ cargo test\x1b[0;m\n\
+                   running 3 tests\n\
+                   progress 10%\rprogress 100%\n\
+                   test api::login ... FAILED\n\
+                   section_end:1700000001:step_script\r\x1b[0K\n\
+                   \x1b[31;1mERROR: Job failed: exit code 101\x1b[0;m\n";
+        assert_eq!(
+            trace_excerpt(raw, 3),
+            "progress 100%\ntest api::login ... FAILED\nERROR: Job failed: exit code 101"
+        );
+        assert!(trace_excerpt(raw, 10).starts_with("$ cargo test"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_against_mock_gitlab() {
+        let server = MockServer::start().await;
+        let base = "/api/v4/projects/group%2Fticketing";
+
+        Mock::given(method("GET"))
+            .and(path(format!("{base}/pipelines")))
+            .and(query_param("ref", "release"))
+            .and(query_param("page", "1"))
+            .and(header("PRIVATE-TOKEN", "t0k"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("X-Next-Page", "2")
+                    .set_body_json(serde_json::json!([
+                        { "id": 12, "status": "failed", "sha": "abc", "web_url": "u12" }
+                    ])),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("{base}/pipelines")))
+            .and(query_param("page", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "id": 11, "status": "success", "sha": "abd", "web_url": "u11" }
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("{base}/pipelines/12/jobs")))
+            .and(query_param("scope[]", "failed"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "id": 99, "name": "test", "stage": "test",
+                  "failure_reason": "script_failure", "web_url": "j99" }
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("{base}/jobs/99/trace")))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok\nboom\n"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("{base}/pipelines/12/test_report")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "test_suites": [{ "name": "rspec", "test_cases": [
+                    { "name": "logs in", "classname": "LoginSpec", "status": "failed" },
+                    { "name": "logs out", "classname": "LoginSpec", "status": "success" }
+                ]}]
+            })))
+            .mount(&server)
+            .await;
+
+        let gitlab = Gitlab::new(GitlabConfig {
+            base_url: server.uri(),
+            token: "t0k".into(),
+            default_project: "group/ticketing".into(),
+            default_branch: "main".into(),
+            max_pipelines: 20,
+            max_failed_jobs: 10,
+            trace_tail_lines: 1,
+        });
+        let range: Range = serde_json::from_value(serde_json::json!({
+            "date_from": "2026-10-01", "date_to": "2026-10-07"
+        }))
+        .unwrap();
+        let params = Params::from([("branch".to_string(), "release".to_string())]);
+
+        let out = gitlab.fetch(&range, &params).await.unwrap();
+        assert_eq!(out.status, "failed");
+        assert_eq!(out.branch, "release");
+        assert_eq!(
+            out.pipelines.iter().map(|p| p.id).collect::<Vec<_>>(),
+            vec![12, 11]
+        );
+        assert_eq!(out.failed_jobs.len(), 1);
+        assert_eq!(out.failed_jobs[0].trace_excerpt, "boom");
+        assert_eq!(out.failed_tests, vec!["rspec: LoginSpec.logs in"]);
+    }
+
+    #[tokio::test]
+    async fn test_upstream_error_body_truncated_on_char_boundary() {
+        let server = MockServer::start().await;
+        // 299 byte ASCII + "é" (2 byte) → byte 300 jatuh di tengah karakter
+        let body = format!("{}é tidak tersedia", "x".repeat(299));
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503).set_body_string(body))
+            .mount(&server)
+            .await;
+
+        let gitlab = Gitlab::new(GitlabConfig {
+            base_url: server.uri(),
+            token: String::new(),
+            default_project: "group/ticketing".into(),
+            default_branch: "main".into(),
+            max_pipelines: 20,
+            max_failed_jobs: 10,
+            trace_tail_lines: 1,
+        });
+        let range: Range = serde_json::from_value(serde_json::json!({})).unwrap();
+        match gitlab.fetch(&range, &Params::new()).await {
+            Err(AdapterError::Upstream(m)) => {
+                assert!(m.ends_with(&"x".repeat(299)), "{m}");
+            }
+            other => panic!("expected upstream error, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,724 @@
+// backend/src/adapters/incident_metrics.rs
+
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlPool, QueryBuilder};
+use std::collections::BTreeMap;
+
+use super::{range_schema, time_bounds, upstream_body, Adapter, AdapterError, AdapterInfo, Params};
+use crate::router::Range;
+use crate::services::ServiceCatalog;
+use crate::util::now_gmt8;
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Incident {
+    pub id: String,
+    pub title: String,
+    pub service: String,
+    pub severity: String,
+    /// open | acknowledged | resolved
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub acknowledged_at: Option<DateTime<Utc>>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    /// Release yang kemungkinan memicu (deploy service sama dalam window korelasi)
+    #[sqlx(skip)]
+    pub release: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Release {
+    pub service: String,
+    pub version: String,
+    pub environment: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// success | failed | rolled_back
+    pub status: String,
+    /// Deploy ini adalah rollback dari versi tsb
+    pub rollback_of: Option<String>,
+    /// Sudah di-rollback (status rolled_back atau ada deploy rollback_of versi ini)
+    #[sqlx(skip)]
+    pub rolled_back: bool,
+    #[sqlx(skip)]
+    pub incidents: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IncidentMetrics {
+    pub adapter: &'static str,
+    /// synthetic | local | pagerduty
+    pub source: &'static str,
+    pub from: String,
+    pub to: String,
+    pub checked_at: String,
+    pub total: usize,
+    pub open: usize,
+    /// Rata-rata menit sampai acknowledge / resolve
+    pub mtta_minutes: Option<f64>,
+    pub mttr_minutes: Option<f64>,
+    pub by_severity: BTreeMap<String, usize>,
+    pub by_service: BTreeMap<String, usize>,
+    /// Incident terbaru (dibatasi `limit`)
+    pub incidents: Vec<Incident>,
+    pub releases: Vec<Release>,
+    /// Jumlah deploy rollback di range
+    pub rollbacks: usize,
+    /// Release (bukan rollback) yang diikuti incident atau di-rollback / total release
+    pub change_failure_rate: Option<f64>,
+}
+
+/* ------------------------- Perhitungan ------------------------- */
+
+fn mean_minutes(durations: impl Iterator<Item = Duration>) -> Option<f64> {
+    let mins: Vec<f64> = durations.map(|d| d.num_seconds() as f64 / 60.0).collect();
+    if mins.is_empty() {
+        return None;
+    }
+    Some((mins.iter().sum::<f64>() / mins.len() as f64 * 10.0).round() / 10.0)
+}
+
+/// Hitung metrik dari incident & release mentah (service sudah dinormalisasi)
+fn metrics(
+    source: &'static str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    mut incidents: Vec<Incident>,
+    mut releases: Vec<Release>,
+    window: Duration,
+    limit: usize,
+) -> IncidentMetrics {
+    // release terbaru dulu supaya incident dikaitkan ke deploy terakhir sebelum incident
+    releases.sort_by_key(|r| std::cmp::Reverse(r.started_at));
+    let rolled_back: Vec<(String, String)> = releases
+        .iter()
+        .filter_map(|r| r.rollback_of.clone().map(|v| (r.service.clone(), v)))
+        .collect();
+    for r in releases.iter_mut() {
+        r.rolled_back = r.status == "rolled_back"
+            || rolled_back.contains(&(r.service.clone(), r.version.clone()));
+    }
+
+    incidents.sort_by_key(|i| std::cmp::Reverse(i.created_at));
+    for i in incidents.iter_mut() {
+        let hit = releases.iter_mut().find(|r| {
+            let deployed = r.finished_at.unwrap_or(r.started_at);
+            r.service == i.service && deployed <= i.created_at && i.created_at <= deployed + window
+        });
+        if let Some(r) = hit {
+            i.release = Some(r.version.clone());
+            r.incidents.push(i.id.clone());
+        }
+    }
+
+    let deploys: Vec<&Release> = releases
+        .iter()
+        .filter(|r| r.rollback_of.is_none())
+        .collect();
+    let failed = deploys
+        .iter()
+        .filter(|r| r.rolled_back || r.status == "failed" || !r.incidents.is_empty())
+        .count();
+    let change_failure_rate = (!deploys.is_empty())
+        .then(|| (failed as f64 / deploys.len() as f64 * 1000.0).round() / 1000.0);
+
+    let count_by = |f: fn(&Incident) -> &String| {
+        incidents.iter().fold(BTreeMap::new(), |mut m, i| {
+            *m.entry(f(i).clone()).or_insert(0) += 1;
+            m
+        })
+    };
+    let by_severity = count_by(|i| &i.severity);
+    let by_service = count_by(|i| &i.service);
+    let mtta_minutes = mean_minutes(
+        incidents
+            .iter()
+            .filter_map(|i| i.acknowledged_at.map(|a| a - i.created_at)),
+    );
+    let mttr_minutes = mean_minutes(
+        incidents
+            .iter()
+            .filter_map(|i| i.resolved_at.map(|r| r - i.created_at)),
+    );
+    let total = incidents.len();
+    let open = incidents.iter().filter(|i| i.resolved_at.is_none()).count();
+    incidents.truncate(limit);
+
+    IncidentMetrics {
+        adapter: "incident_metrics",
+        source,
+        from: from.to_rfc3339(),
+        to: to.to_rfc3339(),
+        checked_at: now_gmt8().to_rfc3339(),
+        total,
+        open,
+        mtta_minutes,
+        mttr_minutes,
+        by_severity,
+        by_service,
+        incidents,
+        rollbacks: releases.iter().filter(|r| r.rollback_of.is_some()).count(),
+        change_failure_rate,
+        releases,
+    }
+}
+
+/* ------------------------- Config ------------------------- */
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum Backend {
+    #[default]
+    Synthetic,
+    /// Tabel incidents + releases di database aplikasi
+    Local(LocalConfig),
+    Pagerduty(PagerDutyConfig),
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LocalConfig {
+    /// Incident dalam n menit setelah deploy dikaitkan ke release itu
+    pub correlation_window_minutes: i64,
+}
+
+impl Default for LocalConfig {
+    fn default() -> Self {
+        Self {
+            correlation_window_minutes: 120,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PagerDutyConfig {
+    #[serde(default = "default_pagerduty_url")]
+    pub base_url: String,
+    /// API token; bisa lewat env PAGERDUTY_TOKEN
+    #[serde(default)]
+    pub token: String,
+    #[serde(default = "default_window")]
+    pub correlation_window_minutes: i64,
+}
+
+fn default_pagerduty_url() -> String {
+    "https://api.pagerduty.com".into()
+}
+fn default_window() -> i64 {
+    120
+}
+
+impl Backend {
+    pub fn validate(&self, errs: &mut Vec<String>) {
+        let window = match self {
+            Backend::Synthetic => return,
+            Backend::Local(l) => l.correlation_window_minutes,
+            Backend::Pagerduty(p) => {
+                if !(p.base_url.starts_with("http://") || p.base_url.starts_with("https://")) {
+                    errs.push(
+                        "adapters.incident_metrics.base_url must start with http:// or https://"
+                            .into(),
+                    );
+                }
+                p.correlation_window_minutes
+            }
+        };
+        if window < 1 {
+            errs.push(
+                "adapters.incident_metrics.correlation_window_minutes must be positive".into(),
+            );
+        }
+    }
+
+    pub fn build(
+        &self,
+        services: &ServiceCatalog,
+        pool: &MySqlPool,
+    ) -> Box<dyn Adapter<Output = IncidentMetrics>> {
+        let store = Store {
+            pool: pool.clone(),
+            services: services.clone(),
+        };
+        match self {
+            Backend::Synthetic => Box::new(Synthetic),
+            Backend::Local(l) => Box::new(Local {
+                window: Duration::minutes(l.correlation_window_minutes),
+                store,
+            }),
+            Backend::Pagerduty(p) => Box::new(PagerDuty::new(p.clone(), store)),
+        }
+    }
+}
+
+fn params_schema() -> serde_json::Value {
+    range_schema(serde_json::json!({
+        "environment": { "type": "string", "default": "production", "description": "environment release untuk korelasi" }
+    }))
+}
+
+/* ------------------------- Synthetic ------------------------- */
+
+/// Tiga release contoh, satu di-rollback dan diikuti incident
+pub struct Synthetic;
+
+impl Adapter for Synthetic {
+    type Output = IncidentMetrics;
+
+    fn describe(&self) -> AdapterInfo {
+        AdapterInfo {
+            domain: "incident_metrics",
+            endpoint: "/api/incident-metrics",
+            backend: "synthetic",
+            description: "Incident, MTTA/MTTR, release dan rollback",
+        }
+    }
+
+    fn params_schema(&self) -> serde_json::Value {
+        params_schema()
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        q: &'a Range,
+        _params: &'a Params,
+    ) -> BoxFuture<'a, Result<IncidentMetrics, AdapterError>> {
+        Box::pin(async move {
+            let (from, to) = time_bounds(q)?;
+            let at = |h: i64| to - Duration::hours(h);
+            let release =
+                |version: &str, h: i64, minutes: i64, rollback_of: Option<&str>| Release {
+                    service: "payments".into(),
+                    version: version.into(),
+                    environment: "production".into(),
+                    started_at: at(h),
+                    finished_at: Some(at(h) + Duration::minutes(minutes)),
+                    status: "success".into(),
+                    rollback_of: rollback_of.map(str::to_string),
+                    rolled_back: false,
+                    incidents: vec![],
+                };
+            let incident = Incident {
+                id: "INC-1001".into(),
+                title: "Payment timeout spike".into(),
+                service: "payments".into(),
+                severity: "SEV2".into(),
+                status: "resolved".into(),
+                created_at: at(9),
+                acknowledged_at: Some(at(9) + Duration::minutes(4)),
+                resolved_at: Some(at(9) + Duration::minutes(38)),
+                release: None,
+            };
+            let releases = vec![
+                release("v1.2.1", 20, 14, None),
+                release("v1.2.2", 10, 20, None),
+                release("v1.2.1", 8, 12, Some("v1.2.2")),
+                release("v1.2.3", 2, 18, None),
+            ];
+            Ok(metrics(
+                "synthetic",
+                from,
+                to,
+                vec![incident],
+                releases,
+                Duration::minutes(120),
+                q.limit.unwrap_or(20),
+            ))
+        })
+    }
+}
+
+/* ------------------------- Local ------------------------- */
+
+/// Akses tabel lokal; release selalu dari tabel `releases`
+struct Store {
+    pool: MySqlPool,
+    services: ServiceCatalog,
+}
+
+impl Store {
+    /// Nama service kanonik (katalog) supaya incident & release bisa dicocokkan
+    fn canonical(&self, s: &str) -> String {
+        self.services
+            .lookup(s)
+            .map(|(n, _)| n.to_string())
+            .unwrap_or_else(|| s.to_string())
+    }
+
+    /// Semua nama yang mungkin tersimpan untuk service (nama, alias, container)
+    fn names(&self, s: &str) -> Vec<String> {
+        let mut names = vec![s.to_string(), self.services.container(s)];
+        if let Some((n, e)) = self.services.lookup(s) {
+            names.push(n.to_string());
+            names.extend(e.aliases.iter().cloned());
+        }
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    fn push_service_filter(&self, qb: &mut QueryBuilder<'_, sqlx::MySql>, service: Option<&str>) {
+        if let Some(s) = service {
+            qb.push(" AND service IN (");
+            let mut sep = qb.separated(", ");
+            for n in self.names(s) {
+                sep.push_bind(n);
+            }
+            qb.push(")");
+        }
+    }
+
+    async fn incidents(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        service: Option<&str>,
+    ) -> Result<Vec<Incident>, AdapterError> {
+        let mut qb = QueryBuilder::new(
+            "SELECT id, title, service, severity, status, created_at, acknowledged_at, resolved_at \
+             FROM incidents WHERE created_at >= ",
+        );
+        qb.push_bind(from).push(" AND created_at < ").push_bind(to);
+        self.push_service_filter(&mut qb, service);
+        qb.push(" ORDER BY created_at DESC LIMIT 5000");
+        let mut rows: Vec<Incident> = qb
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AdapterError::Upstream(format!("incidents: {e}")))?;
+        for r in rows.iter_mut() {
+            r.service = self.canonical(&r.service);
+        }
+        Ok(rows)
+    }
+
+    async fn releases(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        service: Option<&str>,
+        environment: &str,
+    ) -> Result<Vec<Release>, AdapterError> {
+        let mut qb = QueryBuilder::new(
+            "SELECT service, version, environment, started_at, finished_at, status, rollback_of \
+             FROM releases WHERE environment = ",
+        );
+        qb.push_bind(environment.to_string())
+            .push(" AND started_at >= ")
+            .push_bind(from)
+            .push(" AND started_at < ")
+            .push_bind(to);
+        self.push_service_filter(&mut qb, service);
+        qb.push(" ORDER BY started_at DESC LIMIT 1000");
+        let mut rows: Vec<Release> = qb
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AdapterError::Upstream(format!("releases: {e}")))?;
+        for r in rows.iter_mut() {
+            r.service = self.canonical(&r.service);
+        }
+        Ok(rows)
+    }
+}
+
+fn environment(params: &Params) -> &str {
+    params
+        .get("environment")
+        .map(String::as_str)
+        .filter(|e| !e.is_empty())
+        .unwrap_or("production")
+}
+
+/// Backend lokal: tabel incidents + releases
+pub struct Local {
+    window: Duration,
+    store: Store,
+}
+
+impl Adapter for Local {
+    type Output = IncidentMetrics;
+
+    fn describe(&self) -> AdapterInfo {
+        AdapterInfo {
+            domain: "incident_metrics",
+            endpoint: "/api/incident-metrics",
+            backend: "local",
+            description: "MTTA/MTTR, incident per severity/service dan korelasi release/rollback dari tabel lokal",
+        }
+    }
+
+    fn params_schema(&self) -> serde_json::Value {
+        params_schema()
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        q: &'a Range,
+        params: &'a Params,
+    ) -> BoxFuture<'a, Result<IncidentMetrics, AdapterError>> {
+        Box::pin(async move {
+            let (from, to) = time_bounds(q)?;
+            let service = q.service.as_deref().filter(|s| !s.trim().is_empty());
+            let incidents = self.store.incidents(from, to, service).await?;
+            // deploy sebelum awal range masih bisa memicu incident di awal range
+            let releases = self
+                .store
+                .releases(from - self.window, to, service, environment(params))
+                .await?;
+            Ok(metrics(
+                "local",
+                from,
+                to,
+                incidents,
+                releases,
+                self.window,
+                q.limit.unwrap_or(20),
+            ))
+        })
+    }
+}
+
+/* ------------------------- PagerDuty ------------------------- */
+
+#[derive(Debug, Deserialize)]
+struct PdPage {
+    #[serde(default)]
+    data: Vec<PdIncident>,
+    #[serde(default)]
+    more: bool,
+    last: Option<String>,
+}
+
+/// Baris /analytics/raw/incidents
+#[derive(Debug, Deserialize)]
+struct PdIncident {
+    id: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    service_name: String,
+    priority_name: Option<String>,
+    urgency: Option<String>,
+    created_at: DateTime<FixedOffset>,
+    resolved_at: Option<DateTime<FixedOffset>>,
+    seconds_to_first_ack: Option<i64>,
+}
+
+/// Backend PagerDuty: Analytics raw incidents (MTTA dari seconds_to_first_ack),
+/// release dari tabel lokal
+pub struct PagerDuty {
+    cfg: PagerDutyConfig,
+    store: Store,
+    client: reqwest::Client,
+}
+
+impl PagerDuty {
+    fn new(cfg: PagerDutyConfig, store: Store) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(20))
+            .build()
+            .unwrap_or_default();
+        Self { cfg, store, client }
+    }
+
+    async fn incidents(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<Incident>, AdapterError> {
+        let url = format!(
+            "{}/analytics/raw/incidents",
+            self.cfg.base_url.trim_end_matches('/')
+        );
+        let mut out = vec![];
+        let mut cursor: Option<String> = None;
+        // batasi 10 halaman × 1000
+        for _ in 0..10 {
+            let mut body = serde_json::json!({
+                "filters": { "created_at_start": from.to_rfc3339(), "created_at_end": to.to_rfc3339() },
+                "limit": 1000,
+                "order": "desc",
+                "order_by": "created_at",
+            });
+            if let Some(c) = &cursor {
+                body["starting_after"] = c.clone().into();
+            }
+            let resp = self
+                .client
+                .post(&url)
+                .header("Authorization", format!("Token token={}", self.cfg.token))
+                .header("Accept", "application/vnd.pagerduty+json;version=2")
+                .json(&body)
+                .send()
+                .await
+                .map_err(|e| AdapterError::Upstream(format!("pagerduty: {e}")))?;
+            let status = resp.status();
+            if !status.is_success() {
+                let text = resp.text().await.unwrap_or_default();
+                let text = upstream_body(&text);
+                return Err(AdapterError::Upstream(format!(
+                    "pagerduty → {status}: {text}"
+                )));
+            }
+            let page: PdPage = resp.json().await.map_err(|e| {
+                AdapterError::Upstream(format!("pagerduty: unexpected response: {e}"))
+            })?;
+            out.extend(page.data.into_iter().map(|p| {
+                let created_at = p.created_at.with_timezone(&Utc);
+                let acknowledged_at = p
+                    .seconds_to_first_ack
+                    .map(|s| created_at + Duration::seconds(s));
+                let resolved_at = p.resolved_at.map(|r| r.with_timezone(&Utc));
+                Incident {
+                    id: p.id,
+                    title: p.description,
+                    service: self.store.canonical(&p.service_name),
+                    severity: p
+                        .priority_name
+                        .or(p.urgency)
+                        .unwrap_or_else(|| "unknown".into()),
+                    status: match (acknowledged_at, resolved_at) {
+                        (_, Some(_)) => "resolved",
+                        (Some(_), None) => "acknowledged",
+                        _ => "open",
+                    }
+                    .into(),
+                    created_at,
+                    acknowledged_at,
+                    resolved_at,
+                    release: None,
+                }
+            }));
+            cursor = page.last.filter(|_| page.more);
+            if cursor.is_none() {
+                break;
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl Adapter for PagerDuty {
+    type Output = IncidentMetrics;
+
+    fn describe(&self) -> AdapterInfo {
+        AdapterInfo {
+            domain: "incident_metrics",
+            endpoint: "/api/incident-metrics",
+            backend: "pagerduty",
+            description: "Incident PagerDuty (MTTA/MTTR, severity, service) dikorelasikan dengan release lokal",
+        }
+    }
+
+    fn params_schema(&self) -> serde_json::Value {
+        params_schema()
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        q: &'a Range,
+        params: &'a Params,
+    ) -> BoxFuture<'a, Result<IncidentMetrics, AdapterError>> {
+        Box::pin(async move {
+            let (from, to) = time_bounds(q)?;
+            let service = q.service.as_deref().filter(|s| !s.trim().is_empty());
+            let mut incidents = self.incidents(from, to).await?;
+            if let Some(s) = service {
+                let s = self.store.canonical(s);
+                incidents.retain(|i| i.service == s);
+            }
+            let window = Duration::minutes(self.cfg.correlation_window_minutes);
+            let releases = self
+                .store
+                .releases(from - window, to, service, environment(params))
+                .await?;
+            Ok(metrics(
+                "pagerduty",
+                from,
+                to,
+                incidents,
+                releases,
+                window,
+                q.limit.unwrap_or(20),
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_synthetic_correlates_release_and_rollback() {
+        let range: Range = serde_json::from_value(serde_json::json!({})).unwrap();
+        let out = Synthetic.fetch(&range, &Params::new()).await.unwrap();
+        assert_eq!((out.total, out.open, out.rollbacks), (1, 0, 1));
+        assert_eq!(
+            (out.mtta_minutes, out.mttr_minutes),
+            (Some(4.0), Some(38.0))
+        );
+        // incident 9 jam lalu jatuh 1 jam setelah v1.2.2 (10 jam lalu, selesai +20m)
+        assert_eq!(out.incidents[0].release.as_deref(), Some("v1.2.2"));
+        let v122 = out.releases.iter().find(|r| r.version == "v1.2.2").unwrap();
+        assert!(v122.rolled_back);
+        // 3 deploy non-rollback, 1 gagal (v1.2.2)
+        assert_eq!(out.change_failure_rate, Some(0.333));
+        assert_eq!(out.by_severity["SEV2"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_pagerduty_maps_analytics_rows_and_pages() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/analytics/raw/incidents"))
+            .and(header("Authorization", "Token token=pd"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [
+                    { "id": "P1", "description": "Checkout errors", "service_name": "payment",
+                      "priority_name": "P1", "urgency": "high",
+                      "created_at": "2025-10-09T10:00:00+08:00",
+                      "resolved_at": "2025-10-09T10:45:00+08:00", "seconds_to_first_ack": 300 },
+                    { "id": "P2", "description": "Login slow", "service_name": "auth",
+                      "urgency": "low", "created_at": "2025-10-09T11:00:00+08:00" }
+                ],
+                "more": false, "last": "P2"
+            })))
+            .mount(&server)
+            .await;
+
+        let pd = PagerDuty::new(
+            PagerDutyConfig {
+                base_url: server.uri(),
+                token: "pd".into(),
+                correlation_window_minutes: 120,
+            },
+            Store {
+                pool: crate::adapters::tests::lazy_pool(),
+                services: ServiceCatalog::default(),
+            },
+        );
+        let (from, to) = time_bounds(
+            &serde_json::from_value(serde_json::json!({ "date_from": "2025-10-09" })).unwrap(),
+        )
+        .unwrap();
+        let incidents = pd.incidents(from, to).await.unwrap();
+        assert_eq!(incidents.len(), 2);
+        assert_eq!(incidents[0].service, "payments");
+        assert_eq!(incidents[0].status, "resolved");
+        assert_eq!(
+            incidents[0]
+                .acknowledged_at
+                .map(|a| a - incidents[0].created_at),
+            Some(Duration::minutes(5))
+        );
+        assert_eq!(
+            (incidents[1].severity.as_str(), incidents[1].status.as_str()),
+            ("low", "open")
+        );
+    }
+}
@@ -0,0 +1,593 @@
+// backend/src/adapters/mobile_telemetry.rs
+
+use chrono::{DateTime, Duration, Utc};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlPool, QueryBuilder};
+use std::collections::BTreeMap;
+
+use super::{range_schema, time_bounds, Adapter, AdapterError, AdapterInfo, Params};
+use crate::router::Range;
+use crate::util::now_gmt8;
+
+#[derive(Debug, Serialize)]
+pub struct MobileTelemetry {
+    pub adapter: &'static str,
+    /// synthetic | local
+    pub source: &'static str,
+    pub from: String,
+    pub to: String,
+    pub checked_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub os_version: Option<String>,
+    pub sessions: i64,
+    pub crashed_sessions: i64,
+    pub anr_sessions: i64,
+    /// None bila tidak ada sesi
+    pub crash_free_sessions_pct: Option<f64>,
+    pub anr_rate_pct: Option<f64>,
+    pub top_crashes: Vec<CrashSignature>,
+    /// Versi (terbaru, atau `app_version`) vs versi sebelumnya per platform
+    pub version_comparison: Vec<VersionComparison>,
+    pub by_platform: Vec<Breakdown>,
+    pub by_version: Vec<Breakdown>,
+    pub by_os: Vec<Breakdown>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CrashSignature {
+    pub signature: String,
+    /// crash | anr
+    pub kind: String,
+    pub events: i64,
+    pub sessions: i64,
+    pub platforms: Vec<String>,
+    pub app_versions: Vec<String>,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Breakdown {
+    pub platform: String,
+    /// app_version / os_version; kosong untuk by_platform
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub sessions: i64,
+    pub crashed_sessions: i64,
+    pub anr_sessions: i64,
+    pub crash_free_pct: f64,
+    pub anr_rate_pct: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VersionComparison {
+    pub platform: String,
+    pub version: String,
+    pub baseline_version: String,
+    pub crash_free_pct: f64,
+    pub baseline_crash_free_pct: f64,
+    /// Negatif = crash-free turun (lebih banyak crash)
+    pub crash_free_delta: f64,
+    pub anr_rate_pct: f64,
+    pub baseline_anr_rate_pct: f64,
+    pub anr_rate_delta: f64,
+}
+
+/// Jumlah sesi per (platform, app_version, os_version)
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct Cell {
+    pub platform: String,
+    pub app_version: String,
+    pub os_version: String,
+    pub sessions: i64,
+    pub crashed: i64,
+    pub anr: i64,
+}
+
+#[derive(Debug, Default)]
+struct Filters {
+    platform: Option<String>,
+    app_version: Option<String>,
+    os_version: Option<String>,
+    top: usize,
+}
+
+impl Filters {
+    fn parse(q: &Range, params: &Params, top: usize) -> Self {
+        let get = |k: &str| {
+            params
+                .get(k)
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        Self {
+            platform: get("platform").map(|p| p.to_lowercase()),
+            app_version: get("app_version"),
+            os_version: get("os_version"),
+            top: q.limit.unwrap_or(top).clamp(1, 100),
+        }
+    }
+}
+
+fn pct(part: i64, total: i64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    (part as f64 * 10000.0 / total as f64).round() / 100.0
+}
+
+/// "5.10.0" > "5.9.1": bandingkan per segmen numerik
+fn version_key(v: &str) -> Vec<(u64, String)> {
+    v.split(['.', '-', '+'])
+        .map(|s| {
+            let digits: String = s.chars().take_while(char::is_ascii_digit).collect();
+            (digits.parse().unwrap_or(0), s[digits.len()..].to_string())
+        })
+        .collect()
+}
+
+fn breakdown(cells: &[&Cell], key: impl Fn(&Cell) -> Option<String>) -> Vec<Breakdown> {
+    let mut acc: BTreeMap<(String, Option<String>), (i64, i64, i64)> = BTreeMap::new();
+    for c in cells {
+        let e = acc.entry((c.platform.clone(), key(c))).or_default();
+        e.0 += c.sessions;
+        e.1 += c.crashed;
+        e.2 += c.anr;
+    }
+    acc.into_iter()
+        .map(|((platform, key), (sessions, crashed, anr))| Breakdown {
+            platform,
+            key,
+            sessions,
+            crashed_sessions: crashed,
+            anr_sessions: anr,
+            crash_free_pct: pct(sessions - crashed, sessions),
+            anr_rate_pct: pct(anr, sessions),
+        })
+        .collect()
+}
+
+/// Versi fokus (app_version atau terbaru) vs versi sebelumnya di platform yang sama
+fn compare_versions(by_version: &[Breakdown], focus: Option<&str>) -> Vec<VersionComparison> {
+    let mut per_platform: BTreeMap<&str, Vec<&Breakdown>> = BTreeMap::new();
+    for b in by_version {
+        per_platform.entry(&b.platform).or_default().push(b);
+    }
+    per_platform
+        .into_iter()
+        .filter_map(|(platform, mut versions)| {
+            versions.sort_by_key(|b| version_key(b.key.as_deref().unwrap_or_default()));
+            let idx = match focus {
+                Some(v) => versions.iter().position(|b| b.key.as_deref() == Some(v))?,
+                None => versions.len().checked_sub(1)?,
+            };
+            let (cur, base) = (versions[idx], versions[idx.checked_sub(1)?]);
+            Some(VersionComparison {
+                platform: platform.to_string(),
+                version: cur.key.clone().unwrap_or_default(),
+                baseline_version: base.key.clone().unwrap_or_default(),
+                crash_free_pct: cur.crash_free_pct,
+                baseline_crash_free_pct: base.crash_free_pct,
+                crash_free_delta: ((cur.crash_free_pct - base.crash_free_pct) * 100.0).round()
+                    / 100.0,
+                anr_rate_pct: cur.anr_rate_pct,
+                baseline_anr_rate_pct: base.anr_rate_pct,
+                anr_rate_delta: ((cur.anr_rate_pct - base.anr_rate_pct) * 100.0).round() / 100.0,
+            })
+        })
+        .collect()
+}
+
+/// Sel sesi sudah difilter platform/os_version; app_version difilter di sini
+/// supaya by_version dan perbandingan tetap melihat versi lain.
+fn summarize(
+    source: &'static str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    filters: Filters,
+    cells: Vec<Cell>,
+    mut top_crashes: Vec<CrashSignature>,
+) -> MobileTelemetry {
+    let all: Vec<&Cell> = cells.iter().collect();
+    let focused: Vec<&Cell> = cells
+        .iter()
+        .filter(|c| {
+            filters
+                .app_version
+                .as_ref()
+                .is_none_or(|v| &c.app_version == v)
+        })
+        .collect();
+
+    let mut by_version = breakdown(&all, |c| Some(c.app_version.clone()));
+    // terbaru dulu per platform
+    by_version.sort_by(|a, b| {
+        let key = |x: &Breakdown| version_key(x.key.as_deref().unwrap_or_default());
+        a.platform.cmp(&b.platform).then(key(b).cmp(&key(a)))
+    });
+    let version_comparison = compare_versions(&by_version, filters.app_version.as_deref());
+    let (sessions, crashed, anr) = focused.iter().fold((0, 0, 0), |(s, c, a), x| {
+        (s + x.sessions, c + x.crashed, a + x.anr)
+    });
+    top_crashes.sort_by(|a, b| b.events.cmp(&a.events).then(a.signature.cmp(&b.signature)));
+    top_crashes.truncate(filters.top);
+
+    MobileTelemetry {
+        adapter: "mobile_telemetry",
+        source,
+        from: from.to_rfc3339(),
+        to: to.to_rfc3339(),
+        checked_at: now_gmt8().to_rfc3339(),
+        sessions,
+        crashed_sessions: crashed,
+        anr_sessions: anr,
+        crash_free_sessions_pct: (sessions > 0).then(|| pct(sessions - crashed, sessions)),
+        anr_rate_pct: (sessions > 0).then(|| pct(anr, sessions)),
+        top_crashes,
+        version_comparison,
+        by_platform: breakdown(&focused, |_| None),
+        by_version,
+        by_os: breakdown(&focused, |c| Some(c.os_version.clone())),
+        platform: filters.platform,
+        app_version: filters.app_version,
+        os_version: filters.os_version,
+    }
+}
+
+/* ------------------------- Config ------------------------- */
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum Backend {
+    #[default]
+    Synthetic,
+    /// Tabel ingest mobile_sessions + mobile_crashes
+    Local(LocalConfig),
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LocalConfig {
+    /// Jumlah signature crash teratas bila `limit` tidak diisi
+    pub top_crashes: usize,
+}
+
+impl Default for LocalConfig {
+    fn default() -> Self {
+        Self { top_crashes: 10 }
+    }
+}
+
+impl Backend {
+    pub fn validate(&self, errs: &mut Vec<String>) {
+        if let Backend::Local(l) = self {
+            if l.top_crashes == 0 || l.top_crashes > 100 {
+                errs.push("adapters.mobile_telemetry.top_crashes must be 1-100".into());
+            }
+        }
+    }
+
+    pub fn build(&self, pool: &MySqlPool) -> Box<dyn Adapter<Output = MobileTelemetry>> {
+        match self {
+            Backend::Synthetic => Box::new(Synthetic),
+            Backend::Local(l) => Box::new(Local {
+                top_crashes: l.top_crashes,
+                pool: pool.clone(),
+            }),
+        }
+    }
+}
+
+fn params_schema() -> serde_json::Value {
+    range_schema(serde_json::json!({
+        "platform":    { "type": "string", "description": "android | ios" },
+        "app_version": { "type": "string", "description": "versi fokus, dibanding versi sebelumnya" },
+        "os_version":  { "type": "string" }
+    }))
+}
+
+/* ------------------------- Synthetic ------------------------- */
+
+/// Dua versi per platform; 5.2.0 di iOS menaikkan crash
+pub struct Synthetic;
+
+impl Adapter for Synthetic {
+    type Output = MobileTelemetry;
+
+    fn describe(&self) -> AdapterInfo {
+        AdapterInfo {
+            domain: "mobile_telemetry",
+            endpoint: "/api/mobile-telemetry",
+            backend: "synthetic",
+            description: "Crash, ANR dan sesi aplikasi mobile per versi/OS",
+        }
+    }
+
+    fn params_schema(&self) -> serde_json::Value {
+        params_schema()
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        q: &'a Range,
+        params: &'a Params,
+    ) -> BoxFuture<'a, Result<MobileTelemetry, AdapterError>> {
+        Box::pin(async move {
+            let (from, to) = time_bounds(q)?;
+            let filters = Filters::parse(q, params, 10);
+            let cell = |platform: &str, app: &str, os: &str, sessions, crashed, anr| Cell {
+                platform: platform.into(),
+                app_version: app.into(),
+                os_version: os.into(),
+                sessions,
+                crashed,
+                anr,
+            };
+            let cells: Vec<Cell> = [
+                cell("android", "5.1.0", "14", 42_000, 84, 126),
+                cell("android", "5.1.0", "13", 18_000, 54, 72),
+                cell("android", "5.2.0", "14", 30_000, 57, 81),
+                cell("android", "5.2.0", "13", 9_000, 25, 30),
+                cell("ios", "5.1.0", "17.5", 26_000, 26, 13),
+                cell("ios", "5.1.0", "16.7", 6_000, 9, 3),
+                cell("ios", "5.2.0", "17.5", 21_000, 168, 15),
+                cell("ios", "5.2.0", "16.7", 4_000, 22, 2),
+            ]
+            .into_iter()
+            .filter(|c| filters.platform.as_ref().is_none_or(|p| &c.platform == p))
+            .filter(|c| {
+                filters
+                    .os_version
+                    .as_ref()
+                    .is_none_or(|v| &c.os_version == v)
+            })
+            .collect();
+
+            let crash =
+                |signature: &str, kind: &str, events, sessions, platform: &str, app: &str| {
+                    CrashSignature {
+                        signature: signature.into(),
+                        kind: kind.into(),
+                        events,
+                        sessions,
+                        platforms: vec![platform.into()],
+                        app_versions: vec![app.into()],
+                        first_seen: from + Duration::hours(2),
+                        last_seen: to - Duration::minutes(15),
+                    }
+                };
+            let crashes = [
+                crash(
+                    "EXC_BAD_ACCESS PaymentSheetView.render",
+                    "crash",
+                    171,
+                    158,
+                    "ios",
+                    "5.2.0",
+                ),
+                crash(
+                    "java.lang.IllegalStateException: CardFragment not attached",
+                    "crash",
+                    96,
+                    91,
+                    "android",
+                    "5.1.0",
+                ),
+                crash(
+                    "Input dispatching timed out (MainActivity)",
+                    "anr",
+                    88,
+                    80,
+                    "android",
+                    "5.2.0",
+                ),
+                crash(
+                    "NSInternalInconsistencyException UITableView.reloadData",
+                    "crash",
+                    24,
+                    22,
+                    "ios",
+                    "5.1.0",
+                ),
+            ]
+            .into_iter()
+            .filter(|c| {
+                filters
+                    .platform
+                    .as_ref()
+                    .is_none_or(|p| c.platforms.contains(p))
+            })
+            .filter(|c| {
+                filters
+                    .app_version
+                    .as_ref()
+                    .is_none_or(|v| c.app_versions.contains(v))
+            })
+            .collect();
+
+            Ok(summarize("synthetic", from, to, filters, cells, crashes))
+        })
+    }
+}
+
+/* ------------------------- Local (ingest table) ------------------------- */
+
+#[derive(sqlx::FromRow)]
+struct CrashRow {
+    signature: String,
+    kind: String,
+    events: i64,
+    sessions: i64,
+    platforms: String,
+    app_versions: String,
+    first_seen: DateTime<Utc>,
+    last_seen: DateTime<Utc>,
+}
+
+/// Backend tabel ingest dari export SDK mobile
+pub struct Local {
+    top_crashes: usize,
+    pool: MySqlPool,
+}
+
+impl Local {
+    async fn cells(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        f: &Filters,
+    ) -> Result<Vec<Cell>, AdapterError> {
+        let mut qb = QueryBuilder::new(
+            "SELECT platform, app_version, os_version, CAST(COUNT(*) AS SIGNED) AS sessions, \
+             CAST(SUM(crashed) AS SIGNED) AS crashed, CAST(SUM(anr) AS SIGNED) AS anr \
+             FROM mobile_sessions WHERE started_at >= ",
+        );
+        qb.push_bind(from).push(" AND started_at < ").push_bind(to);
+        if let Some(p) = &f.platform {
+            qb.push(" AND platform = ").push_bind(p.clone());
+        }
+        if let Some(v) = &f.os_version {
+            qb.push(" AND os_version = ").push_bind(v.clone());
+        }
+        qb.push(" GROUP BY platform, app_version, os_version");
+        qb.build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AdapterError::Upstream(format!("mobile_sessions: {e}")))
+    }
+
+    async fn crashes(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        f: &Filters,
+    ) -> Result<Vec<CrashSignature>, AdapterError> {
+        let mut qb = QueryBuilder::new(
+            "SELECT signature, kind, CAST(COUNT(*) AS SIGNED) AS events, \
+             CAST(COUNT(DISTINCT session_id) AS SIGNED) AS sessions, \
+             GROUP_CONCAT(DISTINCT platform ORDER BY platform) AS platforms, \
+             GROUP_CONCAT(DISTINCT app_version ORDER BY app_version) AS app_versions, \
+             MIN(occurred_at) AS first_seen, MAX(occurred_at) AS last_seen \
+             FROM mobile_crashes WHERE occurred_at >= ",
+        );
+        qb.push_bind(from).push(" AND occurred_at < ").push_bind(to);
+        if let Some(p) = &f.platform {
+            qb.push(" AND platform = ").push_bind(p.clone());
+        }
+        if let Some(v) = &f.app_version {
+            qb.push(" AND app_version = ").push_bind(v.clone());
+        }
+        if let Some(v) = &f.os_version {
+            qb.push(" AND os_version = ").push_bind(v.clone());
+        }
+        qb.push(" GROUP BY signature, kind ORDER BY events DESC LIMIT ")
+            .push_bind(f.top as i64);
+        let rows: Vec<CrashRow> = qb
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| AdapterError::Upstream(format!("mobile_crashes: {e}")))?;
+        let split = |s: String| s.split(',').map(str::to_string).collect();
+        Ok(rows
+            .into_iter()
+            .map(|r| CrashSignature {
+                signature: r.signature,
+                kind: r.kind,
+                events: r.events,
+                sessions: r.sessions,
+                platforms: split(r.platforms),
+                app_versions: split(r.app_versions),
+                first_seen: r.first_seen,
+                last_seen: r.last_seen,
+            })
+            .collect())
+    }
+}
+
+impl Adapter for Local {
+    type Output = MobileTelemetry;
+
+    fn describe(&self) -> AdapterInfo {
+        AdapterInfo {
+            domain: "mobile_telemetry",
+            endpoint: "/api/mobile-telemetry",
+            backend: "local",
+            description: "Crash-free sessions, crash teratas, ANR rate per versi/OS/platform dari tabel ingest",
+        }
+    }
+
+    fn params_schema(&self) -> serde_json::Value {
+        params_schema()
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        q: &'a Range,
+        params: &'a Params,
+    ) -> BoxFuture<'a, Result<MobileTelemetry, AdapterError>> {
+        Box::pin(async move {
+            let (from, to) = time_bounds(q)?;
+            let filters = Filters::parse(q, params, self.top_crashes);
+            let (cells, crashes) = futures_util::try_join!(
+                self.cells(from, to, &filters),
+                self.crashes(from, to, &filters)
+            )?;
+            Ok(summarize("local", from, to, filters, cells, crashes))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range() -> Range {
+        serde_json::from_value(serde_json::json!({})).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_synthetic_ios_520_regression() {
+        let params = Params::from([
+            ("platform".to_string(), "iOS".to_string()),
+            ("app_version".to_string(), "5.2.0".to_string()),
+        ]);
+        let out = Synthetic.fetch(&range(), &params).await.unwrap();
+        assert_eq!(out.platform.as_deref(), Some("ios"));
+        assert_eq!((out.sessions, out.crashed_sessions), (25_000, 190));
+        assert_eq!(out.crash_free_sessions_pct, Some(99.24));
+        assert_eq!(out.by_version.len(), 2);
+
+        let cmp = &out.version_comparison[0];
+        assert_eq!(
+            (cmp.version.as_str(), cmp.baseline_version.as_str()),
+            ("5.2.0", "5.1.0")
+        );
+        assert!(cmp.crash_free_delta < -0.5);
+        assert_eq!(out.top_crashes[0].platforms, vec!["ios"]);
+        assert_eq!(out.top_crashes.len(), 1);
+    }
+
+    #[test]
+    fn test_compare_versions_orders_numerically() {
+        let b = |v: &str, crashed| Breakdown {
+            platform: "android".into(),
+            key: Some(v.into()),
+            sessions: 1000,
+            crashed_sessions: crashed,
+            anr_sessions: 0,
+            crash_free_pct: pct(1000 - crashed, 1000),
+            anr_rate_pct: 0.0,
+        };
+        let rows = [b("5.10.0", 5), b("5.9.1", 2), b("5.2.0", 1)];
+        let latest = compare_versions(&rows, None);
+        assert_eq!(latest[0].version, "5.10.0");
+        assert_eq!(latest[0].baseline_version, "5.9.1");
+        assert_eq!(latest[0].crash_free_delta, -0.3);
+        assert!(compare_versions(&rows, Some("5.2.0")).is_empty());
+    }
+}
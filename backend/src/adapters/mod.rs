@@ -0,0 +1,356 @@
+// backend/src/adapters/mod.rs
+//
+// Sumber data per domain di balik endpoint /api/*. Tiap domain punya output
+// bertipe sendiri, implementasi synthetic (data contoh), dan backend nyata yang
+// dipilih lewat [adapters.<domain>] di config.
+
+pub mod cloud_mon;
+pub mod data_integration_bi;
+pub mod db_perf;
+pub mod gitlab_ci;
+pub mod incident_metrics;
+pub mod mobile_telemetry;
+pub mod observability;
+pub mod prometheus;
+pub mod runtime_logs;
+pub mod security_auth;
+pub mod sigv4;
+pub mod user_feedback;
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, TimeZone, Utc};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use crate::config::Config;
+use crate::router::Range;
+
+/// Query string mentah; tiap adapter membaca key yang ada di params schema-nya
+pub type Params = HashMap<String, String>;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AdapterInfo {
+    pub domain: &'static str,
+    pub endpoint: &'static str,
+    /// Backend yang aktif ("synthetic", "gitlab", ...)
+    pub backend: &'static str,
+    pub description: &'static str,
+}
+
+#[derive(Debug)]
+pub enum AdapterError {
+    /// Parameter request tidak valid → 400
+    BadParams(String),
+    /// Backend gagal / balasan tidak terduga → 502
+    Upstream(String),
+}
+
+impl std::fmt::Display for AdapterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadParams(m) => write!(f, "bad params: {m}"),
+            Self::Upstream(m) => write!(f, "upstream error: {m}"),
+        }
+    }
+}
+
+impl IntoResponse for AdapterError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            Self::BadParams(_) => StatusCode::BAD_REQUEST,
+            Self::Upstream(_) => {
+                tracing::error!("adapter failed: {self}");
+                StatusCode::BAD_GATEWAY
+            }
+        };
+        (status, self.to_string()).into_response()
+    }
+}
+
+/// Cuplikan body error upstream untuk pesan AdapterError (dipotong di batas karakter)
+pub fn upstream_body(body: &str) -> String {
+    crate::chat_store::truncate_utf8(body, 300).to_string()
+}
+
+pub trait Adapter: Send + Sync {
+    type Output: Serialize + Send;
+
+    fn describe(&self) -> AdapterInfo;
+
+    /// JSON Schema (object) untuk query params yang dibaca `fetch`
+    fn params_schema(&self) -> serde_json::Value;
+
+    fn fetch<'a>(
+        &'a self,
+        range: &'a Range,
+        params: &'a Params,
+    ) -> BoxFuture<'a, Result<Self::Output, AdapterError>>;
+}
+
+/// Params schema dengan field Range bersama + properti khusus adapter
+pub fn range_schema(extra: serde_json::Value) -> serde_json::Value {
+    let mut props = serde_json::json!({
+        "date_from": { "type": "string", "description": "awal range (RFC3339 atau YYYY-MM-DD)" },
+        "date_to":   { "type": "string", "description": "akhir range (RFC3339 atau YYYY-MM-DD)" },
+        "tz":        { "type": "string", "default": "Asia/Singapore" },
+        "service":   { "type": "string" },
+        "limit":     { "type": "integer", "minimum": 1 }
+    });
+    if let (Some(p), Some(e)) = (props.as_object_mut(), extra.as_object()) {
+        p.extend(e.clone());
+    }
+    serde_json::json!({ "type": "object", "properties": props })
+}
+
+/// Rentang waktu dari Range. Tanggal saja (YYYY-MM-DD) dibaca di zona `tz`
+/// (IANA, mis. Asia/Jakarta; default GMT+8) dan date_to tanggal saja berarti
+/// sampai akhir hari itu; default 24 jam terakhir.
+pub fn time_bounds(range: &Range) -> Result<(DateTime<Utc>, DateTime<Utc>), AdapterError> {
+    /// Awal hari `d` di zona `tz` (jam pertama yang ada bila tengah malam kena DST)
+    fn midnight<Tz: TimeZone>(d: NaiveDate, tz: &Tz) -> Option<DateTime<Utc>> {
+        (0..3).find_map(|h| {
+            d.and_hms_opt(h, 0, 0)?
+                .and_local_timezone(tz.clone())
+                .earliest()
+                .map(|t| t.with_timezone(&Utc))
+        })
+    }
+
+    let tz: Option<chrono_tz::Tz> = match range.tz.as_deref().map(str::trim) {
+        Some(v) if !v.is_empty() => Some(
+            v.parse()
+                .map_err(|_| AdapterError::BadParams(format!("unknown tz {v:?}")))?,
+        ),
+        _ => None,
+    };
+    let parse = |v: &str, end_of_day: bool| -> Result<DateTime<Utc>, AdapterError> {
+        if let Ok(t) = DateTime::parse_from_rfc3339(v) {
+            return Ok(t.with_timezone(&Utc));
+        }
+        let d = NaiveDate::parse_from_str(v, "%Y-%m-%d")
+            .map_err(|_| AdapterError::BadParams(format!("{v:?} is not RFC3339 or YYYY-MM-DD")))?;
+        let d = if end_of_day { d + Duration::days(1) } else { d };
+        let t = match &tz {
+            Some(tz) => midnight(d, tz),
+            None => midnight(
+                d,
+                &FixedOffset::east_opt(8 * 3600).expect("valid +08:00 offset"),
+            ),
+        };
+        t.ok_or_else(|| AdapterError::BadParams(format!("{v:?} has no midnight in tz")))
+    };
+
+    let to = match range.date_to.as_deref().filter(|v| !v.is_empty()) {
+        Some(v) => parse(v, true)?,
+        None => Utc::now(),
+    };
+    let from = match range.date_from.as_deref().filter(|v| !v.is_empty()) {
+        Some(v) => parse(v, false)?,
+        None => to - Duration::hours(24),
+    };
+    if from >= to {
+        return Err(AdapterError::BadParams(
+            "date_from must be before date_to".into(),
+        ));
+    }
+    Ok((from, to))
+}
+
+/* ------------------------- Config ------------------------- */
+
+/// Pilihan backend untuk domain yang belum punya backend nyata
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum SyntheticOnly {
+    #[default]
+    Synthetic,
+}
+
+/// Bagian `[adapters.*]` di config.toml
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AdaptersConfig {
+    pub gitlab_ci: gitlab_ci::Backend,
+    pub runtime_logs: runtime_logs::Backend,
+    pub observability: observability::Backend,
+    pub cloud_mon: cloud_mon::Backend,
+    pub db_perf: db_perf::Backend,
+    pub mobile_telemetry: mobile_telemetry::Backend,
+    pub security_auth: security_auth::Backend,
+    pub incident_metrics: incident_metrics::Backend,
+    pub user_feedback: SyntheticOnly,
+    pub data_integration_bi: SyntheticOnly,
+}
+
+impl AdaptersConfig {
+    /// Dipanggil dari Config::validate
+    pub fn validate(&self, errs: &mut Vec<String>) {
+        self.gitlab_ci.validate(errs);
+        self.runtime_logs.validate(errs);
+        self.observability.validate(errs);
+        self.db_perf.validate(errs);
+        self.cloud_mon.validate(errs);
+        self.incident_metrics.validate(errs);
+        self.security_auth.validate(errs);
+        self.mobile_telemetry.validate(errs);
+    }
+}
+
+/* ------------------------- Registry ------------------------- */
+
+type Dyn<T> = Box<dyn Adapter<Output = T>>;
+
+/// Satu adapter aktif per domain
+pub struct Adapters {
+    pub gitlab_ci: Dyn<gitlab_ci::GitlabCi>,
+    pub runtime_logs: Dyn<runtime_logs::RuntimeLogsResp>,
+    pub observability: Dyn<observability::Observability>,
+    pub cloud_mon: Dyn<cloud_mon::CloudMon>,
+    pub db_perf: Dyn<db_perf::DbPerf>,
+    pub mobile_telemetry: Dyn<mobile_telemetry::MobileTelemetry>,
+    pub security_auth: Dyn<security_auth::SecurityAuth>,
+    pub incident_metrics: Dyn<incident_metrics::IncidentMetrics>,
+    pub user_feedback: Dyn<user_feedback::UserFeedback>,
+    pub data_integration_bi: Dyn<data_integration_bi::DataIntegration>,
+}
+
+impl Adapters {
+    pub fn from_config(config: &Config, pool: &MySqlPool) -> Self {
+        let cfg = &config.adapters;
+        Self {
+            gitlab_ci: cfg.gitlab_ci.build(),
+            runtime_logs: cfg.runtime_logs.build(&config.services),
+            observability: cfg.observability.build(&config.services),
+            cloud_mon: cfg.cloud_mon.build(&config.services),
+            db_perf: cfg.db_perf.build(),
+            mobile_telemetry: cfg.mobile_telemetry.build(pool),
+            security_auth: cfg.security_auth.build(&config.services, pool),
+            incident_metrics: cfg.incident_metrics.build(&config.services, pool),
+            user_feedback: Box::new(user_feedback::Synthetic),
+            data_integration_bi: Box::new(data_integration_bi::Synthetic),
+        }
+    }
+
+    /// Katalog untuk GET /api/adapters
+    pub fn describe_all(&self) -> Vec<serde_json::Value> {
+        fn entry<T: Serialize + Send>(a: &Dyn<T>) -> serde_json::Value {
+            serde_json::json!({ "info": a.describe(), "params": a.params_schema() })
+        }
+        vec![
+            entry(&self.gitlab_ci),
+            entry(&self.runtime_logs),
+            entry(&self.observability),
+            entry(&self.cloud_mon),
+            entry(&self.db_perf),
+            entry(&self.mobile_telemetry),
+            entry(&self.security_auth),
+            entry(&self.incident_metrics),
+            entry(&self.user_feedback),
+            entry(&self.data_integration_bi),
+        ]
+    }
+}
+
+/// Adapter aktif yang dibagikan lewat state axum; dibangun ulang saat config reload
+#[derive(Clone)]
+pub struct Registry {
+    current: Arc<RwLock<Arc<Adapters>>>,
+    /// Pool aplikasi untuk backend yang membaca tabel lokal
+    pool: MySqlPool,
+}
+
+impl Registry {
+    pub fn new(cfg: &Config, pool: MySqlPool) -> Self {
+        let adapters = Adapters::from_config(cfg, &pool);
+        Self {
+            current: Arc::new(RwLock::new(Arc::new(adapters))),
+            pool,
+        }
+    }
+
+    pub fn current(&self) -> Arc<Adapters> {
+        self.current
+            .read()
+            .expect("adapter registry poisoned")
+            .clone()
+    }
+
+    pub fn rebuild(&self, cfg: &Config) {
+        let adapters = Arc::new(Adapters::from_config(cfg, &self.pool));
+        *self.current.write().expect("adapter registry poisoned") = adapters;
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::mcp;
+
+    /// Pool lazy; tidak konek selama test tidak menyentuh tabel lokal
+    pub(crate) fn lazy_pool() -> MySqlPool {
+        MySqlPool::connect_lazy(&Config::default().database_url).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_catalog_covers_every_endpoint() {
+        let adapters = Adapters::from_config(&Config::default(), &lazy_pool());
+        let catalog = adapters.describe_all();
+        let endpoints: Vec<&str> = catalog
+            .iter()
+            .filter_map(|e| e["info"]["endpoint"].as_str())
+            .collect();
+        assert_eq!(endpoints.len(), mcp::ENDPOINTS.len());
+        assert!(mcp::ENDPOINTS.iter().all(|ep| endpoints.contains(ep)));
+        assert!(catalog
+            .iter()
+            .all(|e| e["params"]["properties"]["date_from"].is_object()));
+    }
+
+    #[tokio::test]
+    async fn test_synthetic_runtime_logs_honours_limit() {
+        let adapters = Adapters::from_config(&Config::default(), &lazy_pool());
+        let range: Range =
+            serde_json::from_value(serde_json::json!({ "service": "payments", "limit": 3 }))
+                .unwrap();
+        let out = adapters
+            .runtime_logs
+            .fetch(&range, &Params::new())
+            .await
+            .unwrap();
+        assert_eq!(out.logs.len(), 3);
+        assert_eq!(out.container, "payments-service");
+    }
+
+    #[test]
+    fn test_time_bounds_reads_dates_in_requested_tz() {
+        let range = |tz: Option<&str>| -> Range {
+            serde_json::from_value(serde_json::json!({
+                "date_from": "2026-03-08", "date_to": "2026-03-08", "tz": tz
+            }))
+            .unwrap()
+        };
+        let (from, to) = time_bounds(&range(None)).unwrap();
+        assert_eq!(from.to_rfc3339(), "2026-03-07T16:00:00+00:00");
+        assert_eq!(to.to_rfc3339(), "2026-03-08T16:00:00+00:00");
+
+        let (from, to) = time_bounds(&range(Some("Asia/Jakarta"))).unwrap();
+        assert_eq!(from.to_rfc3339(), "2026-03-07T17:00:00+00:00");
+        assert_eq!(to.to_rfc3339(), "2026-03-08T17:00:00+00:00");
+
+        // hari DST di New York hanya 23 jam
+        let (from, to) = time_bounds(&range(Some("America/New_York"))).unwrap();
+        assert_eq!(from.to_rfc3339(), "2026-03-08T05:00:00+00:00");
+        assert_eq!((to - from).num_hours(), 23);
+
+        assert!(matches!(
+            time_bounds(&range(Some("Mars/Olympus"))),
+            Err(AdapterError::BadParams(_))
+        ));
+    }
+}
@@ -0,0 +1,521 @@
+// backend/src/adapters/observability.rs
+
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use futures_util::future::{try_join_all, BoxFuture};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::prometheus::{matchers, PromClient};
+use super::{range_schema, time_bounds, Adapter, AdapterError, AdapterInfo, Params};
+use crate::router::Range;
+use crate::services::ServiceCatalog;
+use crate::util::now_gmt8;
+
+#[derive(Debug, Serialize)]
+pub struct Observability {
+    pub adapter: &'static str,
+    pub service: String,
+    pub from: String,
+    pub to: String,
+    /// Resolusi series dalam detik
+    pub step_secs: i64,
+    pub metrics: Vec<Metric>,
+    pub checked_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Metric {
+    /// latency_p95, error_rate, ...
+    pub name: String,
+    pub unit: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    pub series: Vec<MetricSeries>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MetricSeries {
+    pub labels: BTreeMap<String, String>,
+    pub points: Vec<Point>,
+    pub summary: Summary,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Point {
+    pub ts: String,
+    pub value: f64,
+}
+
+/// Ringkasan series; None bila tidak ada titik
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct Summary {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub avg: Option<f64>,
+    pub last: Option<f64>,
+}
+
+impl Summary {
+    pub fn of(values: &[f64]) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+        Self {
+            min: values.iter().copied().reduce(f64::min),
+            max: values.iter().copied().reduce(f64::max),
+            avg: Some(values.iter().sum::<f64>() / values.len() as f64),
+            last: values.last().copied(),
+        }
+    }
+}
+
+fn series(labels: BTreeMap<String, String>, points: Vec<(DateTime<Utc>, f64)>) -> MetricSeries {
+    let gmt8 = FixedOffset::east_opt(8 * 3600).expect("valid +08:00 offset");
+    let values: Vec<f64> = points.iter().map(|p| p.1).collect();
+    MetricSeries {
+        labels,
+        summary: Summary::of(&values),
+        points: points
+            .into_iter()
+            .map(|(ts, value)| Point {
+                ts: ts.with_timezone(&gmt8).to_rfc3339(),
+                value,
+            })
+            .collect(),
+    }
+}
+
+/* ------------------------- Config ------------------------- */
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum Backend {
+    #[default]
+    Synthetic,
+    Prometheus(PrometheusConfig),
+}
+
+/// Template PromQL; placeholder `$selector`, `$window`, `$error_budget`
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QueryTemplate {
+    pub promql: String,
+    #[serde(default)]
+    pub unit: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PrometheusConfig {
+    /// mis. http://prometheus:9090
+    pub base_url: String,
+    /// Bearer token; bisa lewat env PROMETHEUS_TOKEN
+    #[serde(default)]
+    pub token: String,
+    /// Label service untuk service di luar katalog
+    #[serde(default = "default_service_label")]
+    pub service_label: String,
+    /// Window rate()/histogram, mis. "5m"
+    #[serde(default = "default_rate_window")]
+    pub rate_window: String,
+    /// Target SLO availability (0.999 → error budget 0.001)
+    #[serde(default = "default_slo_target")]
+    pub slo_target: f64,
+    /// Batas titik per series; step = rentang / max_points
+    #[serde(default = "default_max_points")]
+    pub max_points: i64,
+    /// Tambahan/override template default (nama → template)
+    #[serde(default)]
+    pub queries: BTreeMap<String, QueryTemplate>,
+}
+
+fn default_service_label() -> String {
+    "service".into()
+}
+fn default_rate_window() -> String {
+    "5m".into()
+}
+fn default_slo_target() -> f64 {
+    0.999
+}
+fn default_max_points() -> i64 {
+    240
+}
+
+/// Template bawaan untuk metrik HTTP standar (histogram detik + counter status)
+pub fn default_queries() -> BTreeMap<String, QueryTemplate> {
+    let latency = |q: &str| QueryTemplate {
+        promql: format!(
+            "histogram_quantile({q}, sum by (le) \
+             (rate(http_request_duration_seconds_bucket{{$selector}}[$window]))) * 1000"
+        ),
+        unit: "ms".into(),
+    };
+    let error_ratio = "sum(rate(http_requests_total{$selector,status=~\"5..\"}[$window])) \
+                       / sum(rate(http_requests_total{$selector}[$window]))";
+    BTreeMap::from([
+        ("latency_p50".into(), latency("0.50")),
+        ("latency_p95".into(), latency("0.95")),
+        ("latency_p99".into(), latency("0.99")),
+        (
+            "error_rate".into(),
+            QueryTemplate {
+                promql: error_ratio.into(),
+                unit: "ratio".into(),
+            },
+        ),
+        (
+            "request_rate".into(),
+            QueryTemplate {
+                promql: "sum(rate(http_requests_total{$selector}[$window]))".into(),
+                unit: "req/s".into(),
+            },
+        ),
+        (
+            "slo_burn_rate".into(),
+            QueryTemplate {
+                promql: format!("({error_ratio}) / $error_budget"),
+                unit: "x".into(),
+            },
+        ),
+    ])
+}
+
+impl Backend {
+    pub fn validate(&self, errs: &mut Vec<String>) {
+        if let Backend::Prometheus(p) = self {
+            if !(p.base_url.starts_with("http://") || p.base_url.starts_with("https://")) {
+                errs.push(
+                    "adapters.observability.base_url must start with http:// or https://".into(),
+                );
+            }
+            if !(p.slo_target > 0.0 && p.slo_target < 1.0) {
+                errs.push("adapters.observability.slo_target must be between 0 and 1".into());
+            }
+            if p.max_points < 1 {
+                errs.push("adapters.observability.max_points must be positive".into());
+            }
+            if let Some(name) = p
+                .queries
+                .iter()
+                .find(|(_, t)| t.promql.trim().is_empty())
+                .map(|(n, _)| n)
+            {
+                errs.push(format!(
+                    "adapters.observability.queries.{name}: empty promql"
+                ));
+            }
+        }
+    }
+
+    pub fn build(&self, services: &ServiceCatalog) -> Box<dyn Adapter<Output = Observability>> {
+        match self {
+            Backend::Synthetic => Box::new(Synthetic),
+            Backend::Prometheus(p) => Box::new(Prometheus::new(p.clone(), services.clone())),
+        }
+    }
+}
+
+fn params_schema() -> serde_json::Value {
+    range_schema(serde_json::json!({
+        "metrics": { "type": "string", "description": "nama metrik dipisah koma (latency_p95,error_rate); default semua" },
+        "step":    { "type": "integer", "minimum": 1, "description": "resolusi dalam detik" }
+    }))
+}
+
+/// Step (detik): param `step` atau rentang / max_points, minimal 15 detik
+fn step_secs(
+    params: &Params,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    max_points: i64,
+) -> Result<i64, AdapterError> {
+    let span = (to - from).num_seconds().max(1);
+    let min_step = (span + max_points - 1) / max_points;
+    match params.get("step").filter(|s| !s.is_empty()) {
+        Some(s) => {
+            let s: i64 = s
+                .parse()
+                .ok()
+                .filter(|s| *s > 0)
+                .ok_or_else(|| AdapterError::BadParams(format!("invalid step {s}")))?;
+            Ok(s.max(min_step))
+        }
+        None => Ok(min_step.max(15)),
+    }
+}
+
+/// Nama metrik yang diminta; kosong = semua yang tersedia
+fn requested_metrics(params: &Params, available: &[&str]) -> Result<Vec<String>, AdapterError> {
+    let Some(raw) = params.get("metrics").filter(|m| !m.trim().is_empty()) else {
+        return Ok(available.iter().map(|m| m.to_string()).collect());
+    };
+    raw.split(',')
+        .map(|m| {
+            let m = m.trim();
+            if available.contains(&m) {
+                Ok(m.to_string())
+            } else {
+                Err(AdapterError::BadParams(format!(
+                    "unknown metric {m} (available: {})",
+                    available.join(", ")
+                )))
+            }
+        })
+        .collect()
+}
+
+/* ------------------------- Synthetic ------------------------- */
+
+/// Series contoh di sekitar p95 243 ms untuk orders-api
+pub struct Synthetic;
+
+impl Adapter for Synthetic {
+    type Output = Observability;
+
+    fn describe(&self) -> AdapterInfo {
+        AdapterInfo {
+            domain: "observability",
+            endpoint: "/api/observability",
+            backend: "synthetic",
+            description: "Latency, error rate, request rate dan SLO per service",
+        }
+    }
+
+    fn params_schema(&self) -> serde_json::Value {
+        params_schema()
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        q: &'a Range,
+        params: &'a Params,
+    ) -> BoxFuture<'a, Result<Observability, AdapterError>> {
+        Box::pin(async move {
+            let service = q.service.clone().unwrap_or_else(|| "orders-api".into());
+            let (from, to) = time_bounds(q)?;
+            let step = step_secs(params, from, to, 24)?;
+            let defaults = default_queries();
+            let names: Vec<&str> = defaults.keys().map(String::as_str).collect();
+
+            let metrics = requested_metrics(params, &names)?
+                .into_iter()
+                .map(|name| {
+                    let (base, unit) = match name.as_str() {
+                        "latency_p50" => (120.0, "ms"),
+                        "latency_p95" => (243.0, "ms"),
+                        "latency_p99" => (410.0, "ms"),
+                        "error_rate" => (0.004, "ratio"),
+                        "request_rate" => (35.0, "req/s"),
+                        _ => (0.8, "x"),
+                    };
+                    let mut points = vec![];
+                    let mut ts = from;
+                    let mut i = 0u32;
+                    while ts <= to {
+                        // gelombang kecil deterministik ±10%
+                        let wobble = 1.0 + 0.1 * ((i % 6) as f64 - 2.5) / 2.5;
+                        points.push((ts, base * wobble));
+                        ts += Duration::seconds(step);
+                        i += 1;
+                    }
+                    Metric {
+                        name,
+                        unit: unit.into(),
+                        query: None,
+                        series: vec![series(BTreeMap::new(), points)],
+                    }
+                })
+                .collect();
+
+            Ok(Observability {
+                adapter: "observability",
+                service,
+                from: from.to_rfc3339(),
+                to: to.to_rfc3339(),
+                step_secs: step,
+                metrics,
+                checked_at: now_gmt8().to_rfc3339(),
+            })
+        })
+    }
+}
+
+/* ------------------------- Prometheus ------------------------- */
+
+/// Backend Prometheus: template PromQL per metrik, query_range paralel
+pub struct Prometheus {
+    cfg: PrometheusConfig,
+    queries: BTreeMap<String, QueryTemplate>,
+    services: ServiceCatalog,
+    client: PromClient,
+}
+
+impl Prometheus {
+    pub fn new(cfg: PrometheusConfig, services: ServiceCatalog) -> Self {
+        let mut queries = default_queries();
+        queries.extend(cfg.queries.clone());
+        let client = PromClient::new(&cfg.base_url, &cfg.token);
+        Self {
+            cfg,
+            queries,
+            services,
+            client,
+        }
+    }
+
+    /// Isi placeholder template untuk satu service
+    pub fn render(&self, template: &str, service: &str) -> String {
+        let selector = matchers(&self.services.labels(service, &self.cfg.service_label));
+        // dibulatkan supaya 1 - 0.999 tidak jadi 0.0010000000000000009
+        let budget = ((1.0 - self.cfg.slo_target) * 1e9).round() / 1e9;
+        template
+            .replace("$selector", &selector)
+            .replace("$window", &self.cfg.rate_window)
+            .replace("$error_budget", &budget.to_string())
+    }
+}
+
+impl Adapter for Prometheus {
+    type Output = Observability;
+
+    fn describe(&self) -> AdapterInfo {
+        AdapterInfo {
+            domain: "observability",
+            endpoint: "/api/observability",
+            backend: "prometheus",
+            description:
+                "Latency p50/p95/p99, error rate, request rate dan SLO burn rate dari Prometheus",
+        }
+    }
+
+    fn params_schema(&self) -> serde_json::Value {
+        params_schema()
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        q: &'a Range,
+        params: &'a Params,
+    ) -> BoxFuture<'a, Result<Observability, AdapterError>> {
+        Box::pin(async move {
+            let service = q
+                .service
+                .clone()
+                .filter(|s| !s.trim().is_empty())
+                .ok_or_else(|| AdapterError::BadParams("service is required".into()))?;
+            let (from, to) = time_bounds(q)?;
+            let step = step_secs(params, from, to, self.cfg.max_points)?;
+            let names: Vec<&str> = self.queries.keys().map(String::as_str).collect();
+            let wanted = requested_metrics(params, &names)?;
+
+            let metrics = try_join_all(wanted.into_iter().map(|name| async {
+                let t = &self.queries[&name];
+                let query = self.render(&t.promql, &service);
+                let result = self.client.query_range(&query, from, to, step).await?;
+                Ok::<_, AdapterError>(Metric {
+                    name,
+                    unit: t.unit.clone(),
+                    series: result
+                        .into_iter()
+                        .map(|s| series(s.labels, s.points))
+                        .collect(),
+                    query: Some(query),
+                })
+            }))
+            .await?;
+
+            Ok(Observability {
+                adapter: "observability",
+                service,
+                from: from.to_rfc3339(),
+                to: to.to_rfc3339(),
+                step_secs: step,
+                metrics,
+                checked_at: now_gmt8().to_rfc3339(),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_summary_and_render() {
+        let s = Summary::of(&[3.0, 1.0, 2.0]);
+        assert_eq!(
+            (s.min, s.max, s.avg, s.last),
+            (Some(1.0), Some(3.0), Some(2.0), Some(2.0))
+        );
+        assert_eq!(Summary::of(&[]), Summary::default());
+
+        let cfg: Backend =
+            toml::from_str("backend = \"prometheus\"\nbase_url = \"http://prom:9090\"").unwrap();
+        let Backend::Prometheus(p) = cfg else {
+            panic!("expected prometheus")
+        };
+        let p = Prometheus::new(p, ServiceCatalog::default());
+        assert_eq!(
+            p.render(&p.queries["slo_burn_rate"].promql, "payment"),
+            "(sum(rate(http_requests_total{app=\"payments-service\",status=~\"5..\"}[5m])) / \
+             sum(rate(http_requests_total{app=\"payments-service\"}[5m]))) / 0.001"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prometheus_query_range_per_metric() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/query_range"))
+            .and(query_param("query", "sum(rate(http_requests_total{service=\"billing\"}[1m]))"))
+            .and(query_param("step", "3600"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "status": "success",
+                "data": { "resultType": "matrix", "result": [
+                    { "metric": {}, "values": [[1759968000, "10"], [1759971600, "NaN"], [1759975200, "30"]] }
+                ]}
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/query_range"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "status": "error", "errorType": "bad_data", "error": "parse error"
+            })))
+            .mount(&server)
+            .await;
+
+        let cfg: Backend = toml::from_str(&format!(
+            "backend = \"prometheus\"\nbase_url = \"{}\"\nrate_window = \"1m\"",
+            server.uri()
+        ))
+        .unwrap();
+        let adapter = cfg.build(&ServiceCatalog::default());
+        let range: Range = serde_json::from_value(serde_json::json!({
+            "service": "billing",
+            "date_from": "2025-10-09T00:00:00Z", "date_to": "2025-10-09T02:00:00Z"
+        }))
+        .unwrap();
+
+        let params = Params::from([
+            ("metrics".to_string(), "request_rate".to_string()),
+            ("step".to_string(), "3600".to_string()),
+        ]);
+        let out = adapter.fetch(&range, &params).await.unwrap();
+        assert_eq!(out.metrics.len(), 1);
+        let s = &out.metrics[0].series[0];
+        assert_eq!(s.points.len(), 2);
+        assert_eq!(s.summary.avg, Some(20.0));
+        assert_eq!(s.summary.last, Some(30.0));
+
+        let params = Params::from([("metrics".to_string(), "latency_p99".to_string())]);
+        assert!(matches!(
+            adapter.fetch(&range, &params).await,
+            Err(AdapterError::BadParams(_))
+        ));
+    }
+}
@@ -0,0 +1,160 @@
+// backend/src/adapters/prometheus.rs
+//
+// Klien kecil Prometheus HTTP API (/api/v1/query, /api/v1/query_range) yang
+// dipakai bersama oleh adapter observability dan cloud_mon.
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use super::AdapterError;
+
+#[derive(Debug, Deserialize)]
+struct PromResp {
+    status: String,
+    #[serde(default)]
+    data: Option<PromData>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromData {
+    #[serde(default)]
+    result: Vec<RawSeries>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawSeries {
+    #[serde(default)]
+    metric: BTreeMap<String, String>,
+    /// matrix: [[ts, "v"], ...]
+    #[serde(default)]
+    values: Vec<(f64, String)>,
+    /// vector: [ts, "v"]
+    #[serde(default)]
+    value: Option<(f64, String)>,
+}
+
+/// Satu series; nilai NaN/Inf dibuang
+#[derive(Debug, Clone)]
+pub struct Series {
+    pub labels: BTreeMap<String, String>,
+    pub points: Vec<(DateTime<Utc>, f64)>,
+}
+
+impl From<RawSeries> for Series {
+    fn from(r: RawSeries) -> Self {
+        let points = r
+            .values
+            .into_iter()
+            .chain(r.value)
+            .filter_map(|(ts, v)| {
+                let v: f64 = v.parse().ok().filter(|v: &f64| v.is_finite())?;
+                let ts = DateTime::from_timestamp_millis((ts * 1000.0).round() as i64)?;
+                Some((ts, v))
+            })
+            .collect();
+        Self {
+            labels: r.metric,
+            points,
+        }
+    }
+}
+
+/// Escape string literal PromQL (double-quoted)
+pub fn promql_str(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// `app="payments-service",namespace="prod"` (tanpa kurung kurawal)
+pub fn matchers(labels: &BTreeMap<String, String>) -> String {
+    labels
+        .iter()
+        .map(|(k, v)| format!("{k}={}", promql_str(v)))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+pub struct PromClient {
+    base_url: String,
+    token: String,
+    client: reqwest::Client,
+}
+
+impl PromClient {
+    pub fn new(base_url: &str, token: &str) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_default();
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+            client,
+        }
+    }
+
+    pub async fn query_range(
+        &self,
+        query: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        step_secs: i64,
+    ) -> Result<Vec<Series>, AdapterError> {
+        self.get(
+            "query_range",
+            &[
+                ("query", query.to_string()),
+                ("start", start.timestamp().to_string()),
+                ("end", end.timestamp().to_string()),
+                ("step", step_secs.to_string()),
+            ],
+        )
+        .await
+    }
+
+    /// Instant query pada `at`
+    pub async fn query(&self, query: &str, at: DateTime<Utc>) -> Result<Vec<Series>, AdapterError> {
+        self.get(
+            "query",
+            &[
+                ("query", query.to_string()),
+                ("time", at.timestamp().to_string()),
+            ],
+        )
+        .await
+    }
+
+    async fn get(&self, api: &str, q: &[(&str, String)]) -> Result<Vec<Series>, AdapterError> {
+        let mut req = self
+            .client
+            .get(format!("{}/api/v1/{api}", self.base_url))
+            .query(q);
+        if !self.token.is_empty() {
+            req = req.bearer_auth(&self.token);
+        }
+        let resp = req
+            .send()
+            .await
+            .map_err(|e| AdapterError::Upstream(format!("prometheus: {e}")))?;
+        let status = resp.status();
+        // 400/422 tetap berisi JSON {status:"error", error}
+        let body: PromResp = resp.json().await.map_err(|e| {
+            AdapterError::Upstream(format!("prometheus → {status}: unexpected response: {e}"))
+        })?;
+        if body.status != "success" {
+            let msg = body.error.unwrap_or_else(|| status.to_string());
+            return Err(if status.is_client_error() {
+                AdapterError::BadParams(format!("prometheus rejected query: {msg}"))
+            } else {
+                AdapterError::Upstream(format!("prometheus → {status}: {msg}"))
+            });
+        }
+        Ok(body
+            .data
+            .map(|d| d.result.into_iter().map(Series::from).collect())
+            .unwrap_or_default())
+    }
+}
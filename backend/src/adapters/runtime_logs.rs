@@ -0,0 +1,1099 @@
+// backend/src/adapters/runtime_logs.rs
+
+use chrono::{FixedOffset, TimeZone, Utc};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+use super::{range_schema, time_bounds, upstream_body, Adapter, AdapterError, AdapterInfo, Params};
+use crate::router::Range;
+use crate::services::ServiceCatalog;
+use crate::util::now_gmt8;
+
+#[derive(Debug, Serialize)]
+pub struct LogLine {
+    pub ts: String,
+    pub level: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RuntimeLogsResp {
+    pub adapter: &'static str,
+    pub service: String,
+    pub container: String,
+    pub checked_at: String,
+    pub tz: String,
+    pub logs: Vec<LogLine>,
+    /// Query yang dijalankan di backend (LogQL, ...)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    /// Kirim balik sebagai `cursor` untuk halaman berikutnya
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/* ------------------------- Config ------------------------- */
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum Backend {
+    #[default]
+    Synthetic,
+    Loki(LokiConfig),
+    Opensearch(OpenSearchConfig),
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LokiConfig {
+    /// mis. http://loki:3100
+    pub base_url: String,
+    /// Header X-Scope-OrgID (Loki multi-tenant)
+    pub tenant: Option<String>,
+    /// Bearer token (gateway); bisa lewat env LOKI_TOKEN
+    #[serde(default)]
+    pub token: String,
+    /// Label service untuk service di luar katalog
+    #[serde(default = "default_service_label")]
+    pub service_label: String,
+    /// Label level di stream (atau hasil parser); kosong = tebak dari isi baris
+    #[serde(default = "default_level_label")]
+    pub level_label: String,
+    #[serde(default = "default_max_limit")]
+    pub max_limit: usize,
+}
+
+fn default_service_label() -> String {
+    "service_name".into()
+}
+fn default_level_label() -> String {
+    "level".into()
+}
+fn default_max_limit() -> usize {
+    1000
+}
+
+impl Backend {
+    pub fn validate(&self, errs: &mut Vec<String>) {
+        match self {
+            Backend::Synthetic => {}
+            Backend::Loki(l) => {
+                if !(l.base_url.starts_with("http://") || l.base_url.starts_with("https://")) {
+                    errs.push(
+                        "adapters.runtime_logs.base_url must start with http:// or https://".into(),
+                    );
+                }
+                if l.max_limit == 0 {
+                    errs.push("adapters.runtime_logs.max_limit must be positive".into());
+                }
+            }
+            Backend::Opensearch(o) => o.validate(errs),
+        }
+    }
+
+    pub fn build(&self, services: &ServiceCatalog) -> Box<dyn Adapter<Output = RuntimeLogsResp>> {
+        match self {
+            Backend::Synthetic => Box::new(Synthetic {
+                services: services.clone(),
+            }),
+            Backend::Loki(l) => Box::new(Loki::new(l.clone(), services.clone())),
+            Backend::Opensearch(o) => Box::new(OpenSearch::new(o.clone(), services.clone())),
+        }
+    }
+}
+
+fn params_schema() -> serde_json::Value {
+    range_schema(serde_json::json!({
+        "level":     { "type": "string", "description": "filter level, dipisah koma (error,warn)" },
+        "q":         { "type": "string", "description": "teks yang harus ada di baris log" },
+        "direction": { "type": "string", "enum": ["backward", "forward"], "default": "backward" },
+        "cursor":    { "type": "string", "description": "next_cursor dari halaman sebelumnya (opaque)" }
+    }))
+}
+
+/// Level yang diminta (uppercase); kosong = semua
+fn requested_levels(params: &Params) -> Result<Vec<String>, AdapterError> {
+    const KNOWN: &[&str] = &["TRACE", "DEBUG", "INFO", "WARN", "ERROR", "FATAL"];
+    let Some(raw) = params.get("level").filter(|l| !l.trim().is_empty()) else {
+        return Ok(vec![]);
+    };
+    raw.split(',')
+        .map(|l| {
+            let l = l.trim().to_uppercase();
+            let l = if l == "WARNING" {
+                "WARN".to_string()
+            } else {
+                l
+            };
+            if KNOWN.contains(&l.as_str()) {
+                Ok(l)
+            } else {
+                Err(AdapterError::BadParams(format!("unknown level {l}")))
+            }
+        })
+        .collect()
+}
+
+/// `direction=forward` → true (lama → baru); default backward
+fn forward(params: &Params) -> Result<bool, AdapterError> {
+    match params.get("direction").map(String::as_str) {
+        None | Some("") | Some("backward") => Ok(false),
+        Some("forward") => Ok(true),
+        Some(other) => Err(AdapterError::BadParams(format!(
+            "direction must be backward or forward, got {other}"
+        ))),
+    }
+}
+
+fn required_service(q: &Range) -> Result<String, AdapterError> {
+    q.service
+        .clone()
+        .filter(|s| !s.trim().is_empty())
+        .ok_or_else(|| AdapterError::BadParams("service is required".into()))
+}
+
+/// Tebak level dari isi baris bila backend tidak punya field/label level
+fn detect_level(line: &str) -> String {
+    let upper = line.to_uppercase();
+    ["FATAL", "ERROR", "WARN", "INFO", "DEBUG", "TRACE"]
+        .into_iter()
+        .find(|l| upper.contains(l))
+        .unwrap_or("UNKNOWN")
+        .to_string()
+}
+
+fn gmt8() -> FixedOffset {
+    FixedOffset::east_opt(8 * 3600).expect("valid +08:00 offset")
+}
+
+/* ------------------------- Synthetic ------------------------- */
+
+/// Baris log sintetis bergilir INFO/DEBUG/WARN/ERROR
+pub struct Synthetic {
+    services: ServiceCatalog,
+}
+
+impl Adapter for Synthetic {
+    type Output = RuntimeLogsResp;
+
+    fn describe(&self) -> AdapterInfo {
+        AdapterInfo {
+            domain: "runtime_logs",
+            endpoint: "/api/runtime-logs",
+            backend: "synthetic",
+            description: "Log runtime container/service",
+        }
+    }
+
+    fn params_schema(&self) -> serde_json::Value {
+        params_schema()
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        q: &'a Range,
+        params: &'a Params,
+    ) -> BoxFuture<'a, Result<RuntimeLogsResp, AdapterError>> {
+        Box::pin(async move {
+            let tz = q.tz.as_deref().unwrap_or("Asia/Singapore").to_string();
+            let service = q.service.clone().unwrap_or_else(|| "unknown".to_string());
+            let limit = q.limit.unwrap_or(5).clamp(1, 200);
+            let container = self.services.container(&service);
+            let levels = requested_levels(params)?;
+
+            let mut logs = Vec::with_capacity(limit);
+            for i in 0..limit {
+                let level = match i % 5 {
+                    0 => "INFO",
+                    1 => "DEBUG",
+                    2 => "WARN",
+                    3 => "ERROR",
+                    _ => "INFO",
+                };
+                if !levels.is_empty() && !levels.iter().any(|l| l == level) {
+                    continue;
+                }
+                logs.push(LogLine {
+                    ts: now_gmt8().to_rfc3339(),
+                    level: level.to_string(),
+                    message: format!(
+                        "[{service}] line #{i} — synthetic log line for {container} (tz={tz})"
+                    ),
+                });
+            }
+
+            Ok(RuntimeLogsResp {
+                adapter: "runtime_logs",
+                service,
+                container,
+                checked_at: now_gmt8().to_rfc3339(),
+                tz,
+                logs,
+                query: None,
+                next_cursor: None,
+            })
+        })
+    }
+}
+
+/* ------------------------- Loki ------------------------- */
+
+#[derive(Debug, Deserialize)]
+struct LokiResp {
+    data: LokiData,
+}
+
+#[derive(Debug, Deserialize)]
+struct LokiData {
+    #[serde(default)]
+    result: Vec<LokiStream>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LokiStream {
+    #[serde(default)]
+    stream: BTreeMap<String, String>,
+    /// [timestamp ns (string), baris]
+    #[serde(default)]
+    values: Vec<(String, String)>,
+}
+
+/// Backend Loki: GET /loki/api/v1/query_range dengan LogQL dari katalog service
+pub struct Loki {
+    cfg: LokiConfig,
+    services: ServiceCatalog,
+    client: reqwest::Client,
+}
+
+/// Escape string literal LogQL (double-quoted)
+fn logql_str(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl Loki {
+    pub fn new(cfg: LokiConfig, services: ServiceCatalog) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(20))
+            .build()
+            .unwrap_or_default();
+        Self {
+            cfg,
+            services,
+            client,
+        }
+    }
+
+    /// `{labels} |= "q" | level=~"(?i)error|warn"`
+    pub fn logql(&self, service: &str, levels: &[String], text: Option<&str>) -> String {
+        let selector = self
+            .services
+            .labels(service, &self.cfg.service_label)
+            .iter()
+            .map(|(k, v)| format!("{k}={}", logql_str(v)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut q = format!("{{{selector}}}");
+        if let Some(t) = text.filter(|t| !t.trim().is_empty()) {
+            q.push_str(&format!(" |= {}", logql_str(t)));
+        }
+        if !levels.is_empty() {
+            if self.cfg.level_label.is_empty() {
+                let alts = levels.join("|");
+                q.push_str(&format!(
+                    " |~ {}",
+                    logql_str(&format!("(?i)\\b({alts})\\b"))
+                ));
+            } else {
+                let alts = levels
+                    .iter()
+                    .map(|l| l.to_lowercase())
+                    .collect::<Vec<_>>()
+                    .join("|");
+                q.push_str(&format!(
+                    " | {}=~{}",
+                    self.cfg.level_label,
+                    logql_str(&format!("(?i)({alts})"))
+                ));
+            }
+        }
+        q
+    }
+
+    fn level_of(&self, labels: &BTreeMap<String, String>, line: &str) -> String {
+        match labels.get(&self.cfg.level_label) {
+            Some(l) => l.to_uppercase(),
+            None => detect_level(line),
+        }
+    }
+}
+
+/// Cursor Loki `<ns>:<hash>,<hash>`: timestamp baris terakhir + hash baris di
+/// timestamp itu yang sudah dikirim. Halaman berikutnya menyertakan lagi
+/// timestamp tsb dan membuang baris yang sudah terlihat, jadi baris dengan ns
+/// sama tidak hilang maupun terduplikasi di batas halaman.
+#[derive(Debug, Default, PartialEq)]
+struct LokiCursor {
+    ns: i64,
+    seen: BTreeSet<String>,
+}
+
+impl LokiCursor {
+    fn parse(c: &str) -> Result<Self, AdapterError> {
+        let bad = || AdapterError::BadParams(format!("invalid cursor {c}"));
+        let (ns, seen) = c.split_once(':').unwrap_or((c, ""));
+        Ok(Self {
+            ns: ns.parse().map_err(|_| bad())?,
+            seen: seen
+                .split(',')
+                .filter(|h| !h.is_empty())
+                .map(str::to_string)
+                .collect(),
+        })
+    }
+
+    fn encode(&self) -> String {
+        let seen: Vec<&str> = self.seen.iter().map(String::as_str).collect();
+        format!("{}:{}", self.ns, seen.join(","))
+    }
+
+    fn line_hash(line: &str) -> String {
+        hex::encode(&Sha256::digest(line.as_bytes())[..6])
+    }
+}
+
+impl Adapter for Loki {
+    type Output = RuntimeLogsResp;
+
+    fn describe(&self) -> AdapterInfo {
+        AdapterInfo {
+            domain: "runtime_logs",
+            endpoint: "/api/runtime-logs",
+            backend: "loki",
+            description: "Log runtime dari Loki (LogQL per service, filter level & teks)",
+        }
+    }
+
+    fn params_schema(&self) -> serde_json::Value {
+        params_schema()
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        q: &'a Range,
+        params: &'a Params,
+    ) -> BoxFuture<'a, Result<RuntimeLogsResp, AdapterError>> {
+        Box::pin(async move {
+            let tz = q.tz.as_deref().unwrap_or("Asia/Singapore").to_string();
+            let service = required_service(q)?;
+            let limit = q.limit.unwrap_or(100).clamp(1, self.cfg.max_limit);
+            let levels = requested_levels(params)?;
+            let forward = forward(params)?;
+
+            let (from, to) = time_bounds(q)?;
+            let (mut start, mut end) = (
+                from.timestamp_nanos_opt().unwrap_or_default(),
+                to.timestamp_nanos_opt().unwrap_or_default(),
+            );
+            // timestamp cursor ikut diminta lagi (end Loki eksklusif → c + 1);
+            // baris yang sudah terkirim dibuang setelah respons diterima
+            let cursor = match params.get("cursor").filter(|c| !c.is_empty()) {
+                Some(c) => Some(LokiCursor::parse(c)?),
+                None => None,
+            };
+            if let Some(c) = &cursor {
+                if forward {
+                    start = start.max(c.ns);
+                } else {
+                    end = end.min(c.ns + 1);
+                }
+            }
+            let fetch_limit = limit + cursor.as_ref().map_or(0, |c| c.seen.len());
+
+            let query = self.logql(&service, &levels, params.get("q").map(String::as_str));
+            let url = format!(
+                "{}/loki/api/v1/query_range",
+                self.cfg.base_url.trim_end_matches('/')
+            );
+            let mut req = self.client.get(&url).query(&[
+                ("query", query.clone()),
+                ("start", start.to_string()),
+                ("end", end.to_string()),
+                ("limit", fetch_limit.to_string()),
+                (
+                    "direction",
+                    if forward { "forward" } else { "backward" }.to_string(),
+                ),
+            ]);
+            if let Some(t) = &self.cfg.tenant {
+                req = req.header("X-Scope-OrgID", t);
+            }
+            if !self.cfg.token.is_empty() {
+                req = req.bearer_auth(&self.cfg.token);
+            }
+            let resp = req
+                .send()
+                .await
+                .map_err(|e| AdapterError::Upstream(format!("loki: {e}")))?;
+            let status = resp.status();
+            if !status.is_success() {
+                let body = resp.text().await.unwrap_or_default();
+                let body = upstream_body(&body);
+                // 400 dari Loki = LogQL/parameter salah
+                return Err(if status == reqwest::StatusCode::BAD_REQUEST {
+                    AdapterError::BadParams(format!("loki rejected query: {body}"))
+                } else {
+                    AdapterError::Upstream(format!("loki → {status}: {body}"))
+                });
+            }
+            let body: LokiResp = resp
+                .json()
+                .await
+                .map_err(|e| AdapterError::Upstream(format!("loki: unexpected response: {e}")))?;
+
+            // gabungkan semua stream lalu urutkan sesuai direction
+            let mut entries: Vec<(i64, String, String)> = Vec::new();
+            for s in body.data.result {
+                for (ts, line) in s.values {
+                    let Ok(ns) = ts.parse::<i64>() else { continue };
+                    if let Some(c) = &cursor {
+                        let past = if forward { ns < c.ns } else { ns > c.ns };
+                        if past || (ns == c.ns && c.seen.contains(&LokiCursor::line_hash(&line))) {
+                            continue;
+                        }
+                    }
+                    let level = self.level_of(&s.stream, &line);
+                    entries.push((ns, level, line));
+                }
+            }
+            entries.sort_by(|a, b| (a.0, &a.2).cmp(&(b.0, &b.2)));
+            if !forward {
+                entries.reverse();
+            }
+            entries.truncate(limit);
+            let next_cursor = (entries.len() == limit)
+                .then(|| entries.last().map(|e| e.0))
+                .flatten()
+                .map(|ns| {
+                    let mut next = LokiCursor {
+                        ns,
+                        seen: entries
+                            .iter()
+                            .filter(|e| e.0 == ns)
+                            .map(|e| LokiCursor::line_hash(&e.2))
+                            .collect(),
+                    };
+                    // masih di timestamp yang sama → bawa juga baris halaman sebelumnya
+                    if let Some(c) = cursor.filter(|c| c.ns == ns) {
+                        next.seen.extend(c.seen);
+                    }
+                    next.encode()
+                });
+
+            let logs = entries
+                .into_iter()
+                .map(|(ns, level, message)| LogLine {
+                    ts: Utc.timestamp_nanos(ns).with_timezone(&gmt8()).to_rfc3339(),
+                    level,
+                    message,
+                })
+                .collect();
+
+            Ok(RuntimeLogsResp {
+                adapter: "runtime_logs",
+                container: self.services.container(&service),
+                service,
+                checked_at: now_gmt8().to_rfc3339(),
+                tz,
+                logs,
+                query: Some(query),
+                next_cursor,
+            })
+        })
+    }
+}
+
+/* ------------------------- OpenSearch ------------------------- */
+
+/// Nama field di dokumen log; default mengikuti ECS
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FieldMap {
+    pub timestamp: String,
+    pub service: String,
+    pub level: String,
+    pub message: String,
+    /// Field unik sebagai sort kedua, supaya hit dengan timestamp sama tidak
+    /// terlewat antar halaman (`_id`; di Elasticsearch 8 pakai field keyword unik)
+    pub tiebreaker: String,
+}
+
+impl Default for FieldMap {
+    fn default() -> Self {
+        Self {
+            timestamp: "@timestamp".into(),
+            service: "service.name".into(),
+            level: "log.level".into(),
+            message: "message".into(),
+            tiebreaker: "_id".into(),
+        }
+    }
+}
+
+/// Satu index/pattern dan service yang log-nya ada di sana
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IndexConfig {
+    /// mis. "logs-payments-*"
+    pub pattern: String,
+    /// Nama/alias service (katalog); kosong = index fallback
+    #[serde(default)]
+    pub services: Vec<String>,
+    #[serde(default)]
+    pub fields: FieldMap,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OpenSearchConfig {
+    /// mis. https://opensearch:9200
+    pub base_url: String,
+    #[serde(default)]
+    pub username: String,
+    /// Basic auth; bisa lewat env OPENSEARCH_PASSWORD
+    #[serde(default)]
+    pub password: String,
+    pub indices: Vec<IndexConfig>,
+    #[serde(default = "default_max_limit")]
+    pub max_limit: usize,
+}
+
+impl OpenSearchConfig {
+    fn validate(&self, errs: &mut Vec<String>) {
+        if !(self.base_url.starts_with("http://") || self.base_url.starts_with("https://")) {
+            errs.push("adapters.runtime_logs.base_url must start with http:// or https://".into());
+        }
+        if self.indices.is_empty() {
+            errs.push("adapters.runtime_logs.indices must not be empty".into());
+        }
+        if let Some(i) = self.indices.iter().find(|i| i.pattern.trim().is_empty()) {
+            errs.push(format!(
+                "adapters.runtime_logs.indices: empty pattern (services {:?})",
+                i.services
+            ));
+        }
+        if self.max_limit == 0 {
+            errs.push("adapters.runtime_logs.max_limit must be positive".into());
+        }
+    }
+}
+
+/// Backend OpenSearch/Elasticsearch: POST /<index>/_search per service
+pub struct OpenSearch {
+    cfg: OpenSearchConfig,
+    services: ServiceCatalog,
+    client: reqwest::Client,
+}
+
+/// Ambil field bertitik dari _source: key literal ("log.level") atau nested
+fn source_field<'v>(src: &'v serde_json::Value, path: &str) -> Option<&'v serde_json::Value> {
+    if let Some(v) = src.get(path) {
+        return Some(v);
+    }
+    let (head, rest) = path.split_once('.')?;
+    source_field(src.get(head)?, rest)
+}
+
+fn source_str(src: &serde_json::Value, path: &str) -> Option<String> {
+    match source_field(src, path)? {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Null => None,
+        other => Some(other.to_string()),
+    }
+}
+
+impl OpenSearch {
+    pub fn new(cfg: OpenSearchConfig, services: ServiceCatalog) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(20))
+            .build()
+            .unwrap_or_default();
+        Self {
+            cfg,
+            services,
+            client,
+        }
+    }
+
+    /// Index untuk service: yang mencantumkan service itu, lalu fallback
+    fn index_for(&self, service: &str) -> Option<&IndexConfig> {
+        let canonical = self.services.lookup(service).map(|(n, _)| n);
+        let listed = |i: &&IndexConfig| {
+            i.services.iter().any(|s| {
+                s.eq_ignore_ascii_case(service)
+                    || Some(s.as_str()) == canonical
+                    || self
+                        .services
+                        .lookup(s)
+                        .map(|(n, _)| n)
+                        .is_some_and(|n| Some(n) == canonical)
+            })
+        };
+        self.cfg
+            .indices
+            .iter()
+            .find(listed)
+            .or_else(|| self.cfg.indices.iter().find(|i| i.services.is_empty()))
+    }
+
+    /// Body `_search`: filter service/level/waktu, `q` via simple_query_string
+    #[allow(clippy::too_many_arguments)]
+    pub fn search_body(
+        &self,
+        fields: &FieldMap,
+        service: &str,
+        levels: &[String],
+        text: Option<&str>,
+        range: (String, String),
+        limit: usize,
+        forward: bool,
+        cursor: Option<Vec<serde_json::Value>>,
+    ) -> serde_json::Value {
+        // nilai service di dokumen bisa nama kanonik atau nama container
+        let mut names = vec![service.to_string(), self.services.container(service)];
+        if let Some((n, _)) = self.services.lookup(service) {
+            names.push(n.to_string());
+        }
+        names.sort();
+        names.dedup();
+
+        let mut filter = vec![
+            serde_json::json!({ "terms": { fields.service.clone(): names } }),
+            serde_json::json!({ "range": { fields.timestamp.clone(): {
+                "gte": range.0, "lt": range.1, "format": "strict_date_optional_time"
+            } } }),
+        ];
+        if !levels.is_empty() {
+            let variants: Vec<String> = levels
+                .iter()
+                .flat_map(|l| [l.clone(), l.to_lowercase()])
+                .chain(
+                    levels
+                        .iter()
+                        .filter(|l| *l == "WARN")
+                        .map(|_| "warning".to_string()),
+                )
+                .collect();
+            filter.push(serde_json::json!({ "terms": { fields.level.clone(): variants } }));
+        }
+        let mut must = vec![];
+        if let Some(t) = text.filter(|t| !t.trim().is_empty()) {
+            must.push(serde_json::json!({ "simple_query_string": {
+                "query": t, "fields": [fields.message], "default_operator": "and"
+            } }));
+        }
+
+        let order = if forward { "asc" } else { "desc" };
+        let mut body = serde_json::json!({
+            "size": limit,
+            "timeout": "10s",
+            "track_total_hits": false,
+            "_source": [fields.timestamp, fields.level, fields.message],
+            "query": { "bool": { "filter": filter, "must": must } },
+            "sort": [
+                { fields.timestamp.clone(): { "order": order } },
+                { fields.tiebreaker.clone(): { "order": order } },
+            ],
+        });
+        if let Some(c) = cursor {
+            body["search_after"] = serde_json::Value::Array(c);
+        }
+        body
+    }
+}
+
+impl Adapter for OpenSearch {
+    type Output = RuntimeLogsResp;
+
+    fn describe(&self) -> AdapterInfo {
+        AdapterInfo {
+            domain: "runtime_logs",
+            endpoint: "/api/runtime-logs",
+            backend: "opensearch",
+            description: "Log runtime dari OpenSearch/Elasticsearch (_search per index service)",
+        }
+    }
+
+    fn params_schema(&self) -> serde_json::Value {
+        params_schema()
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        q: &'a Range,
+        params: &'a Params,
+    ) -> BoxFuture<'a, Result<RuntimeLogsResp, AdapterError>> {
+        Box::pin(async move {
+            let tz = q.tz.as_deref().unwrap_or("Asia/Singapore").to_string();
+            let service = required_service(q)?;
+            let limit = q.limit.unwrap_or(100).clamp(1, self.cfg.max_limit);
+            let levels = requested_levels(params)?;
+            let forward = forward(params)?;
+            let (from, to) = time_bounds(q)?;
+            // cursor = array `sort` (timestamp, tiebreaker) hit terakhir halaman sebelumnya
+            let cursor = match params.get("cursor").filter(|c| !c.is_empty()) {
+                Some(c) => Some(
+                    serde_json::from_str::<Vec<serde_json::Value>>(c)
+                        .ok()
+                        .filter(|v| v.len() == 2)
+                        .ok_or_else(|| AdapterError::BadParams(format!("invalid cursor {c}")))?,
+                ),
+                None => None,
+            };
+            let index = self.index_for(&service).ok_or_else(|| {
+                AdapterError::BadParams(format!("no OpenSearch index configured for {service}"))
+            })?;
+            let fields = &index.fields;
+
+            let body = self.search_body(
+                fields,
+                &service,
+                &levels,
+                params.get("q").map(String::as_str),
+                (from.to_rfc3339(), to.to_rfc3339()),
+                limit,
+                forward,
+                cursor,
+            );
+
+            let mut url = reqwest::Url::parse(&self.cfg.base_url)
+                .map_err(|e| AdapterError::Upstream(format!("opensearch base_url: {e}")))?;
+            url.path_segments_mut()
+                .map_err(|_| AdapterError::Upstream("opensearch base_url cannot be a base".into()))?
+                .pop_if_empty()
+                .extend([index.pattern.as_str(), "_search"]);
+            let mut req = self.client.post(url).json(&body);
+            if !self.cfg.username.is_empty() {
+                req = req.basic_auth(&self.cfg.username, Some(&self.cfg.password));
+            }
+            let resp = req
+                .send()
+                .await
+                .map_err(|e| AdapterError::Upstream(format!("opensearch: {e}")))?;
+            let status = resp.status();
+            if !status.is_success() {
+                let text = resp.text().await.unwrap_or_default();
+                let text = upstream_body(&text);
+                return Err(if status == reqwest::StatusCode::BAD_REQUEST {
+                    AdapterError::BadParams(format!("opensearch rejected query: {text}"))
+                } else {
+                    AdapterError::Upstream(format!("opensearch → {status}: {text}"))
+                });
+            }
+            let out: serde_json::Value = resp.json().await.map_err(|e| {
+                AdapterError::Upstream(format!("opensearch: unexpected response: {e}"))
+            })?;
+            let hits = out["hits"]["hits"].as_array().cloned().unwrap_or_default();
+
+            let mut last_sort = None;
+            let logs: Vec<LogLine> = hits
+                .iter()
+                .map(|h| {
+                    let src = &h["_source"];
+                    if let Some(sort) = h["sort"].as_array() {
+                        last_sort = Some(sort.clone());
+                    }
+                    let message = source_str(src, &fields.message).unwrap_or_default();
+                    let ts = source_str(src, &fields.timestamp)
+                        .and_then(|t| chrono::DateTime::parse_from_rfc3339(&t).ok())
+                        .map(|t| t.with_timezone(&gmt8()).to_rfc3339())
+                        .or_else(|| {
+                            h["sort"][0].as_i64().and_then(|ms| {
+                                Utc.timestamp_millis_opt(ms)
+                                    .single()
+                                    .map(|t| t.with_timezone(&gmt8()).to_rfc3339())
+                            })
+                        })
+                        .unwrap_or_default();
+                    let level = source_str(src, &fields.level)
+                        .map(|l| match l.to_uppercase().as_str() {
+                            "WARNING" => "WARN".to_string(),
+                            u => u.to_string(),
+                        })
+                        .unwrap_or_else(|| detect_level(&message));
+                    LogLine { ts, level, message }
+                })
+                .collect();
+            let next_cursor = (logs.len() == limit)
+                .then(|| last_sort.map(|s| serde_json::Value::Array(s).to_string()))
+                .flatten();
+
+            Ok(RuntimeLogsResp {
+                adapter: "runtime_logs",
+                container: self.services.container(&service),
+                service,
+                checked_at: now_gmt8().to_rfc3339(),
+                tz,
+                logs,
+                query: Some(body.to_string()),
+                next_cursor,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn loki(base_url: String) -> Loki {
+        Loki::new(
+            LokiConfig {
+                base_url,
+                tenant: Some("smrt".into()),
+                token: String::new(),
+                service_label: "service_name".into(),
+                level_label: "level".into(),
+                max_limit: 1000,
+            },
+            ServiceCatalog::default(),
+        )
+    }
+
+    #[test]
+    fn test_logql_from_catalog_level_and_text() {
+        let l = loki("http://loki".into());
+        assert_eq!(
+            l.logql(
+                "payment",
+                &["ERROR".into(), "WARN".into()],
+                Some("timeout \"db\"")
+            ),
+            r#"{app="payments-service"} |= "timeout \"db\"" | level=~"(?i)(error|warn)""#
+        );
+        assert_eq!(l.logql("billing", &[], None), r#"{service_name="billing"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_merges_streams_and_pages_backward() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/loki/api/v1/query_range"))
+            .and(query_param("direction", "backward"))
+            .and(query_param("limit", "2"))
+            .and(header("X-Scope-OrgID", "smrt"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "status": "success",
+                "data": { "resultType": "streams", "result": [
+                    { "stream": { "app": "payments-service", "level": "error" },
+                      "values": [["1760000000000000300", "payment timeout"]] },
+                    { "stream": { "app": "payments-service" },
+                      "values": [["1760000000000000200", "WARN retrying"],
+                                 ["1760000000000000100", "INFO started"]] }
+                ]}
+            })))
+            .mount(&server)
+            .await;
+
+        let range: Range = serde_json::from_value(serde_json::json!({
+            "service": "payments", "limit": 2,
+            "date_from": "2025-10-09T00:00:00Z", "date_to": "2025-10-10T00:00:00Z"
+        }))
+        .unwrap();
+        let out = loki(server.uri())
+            .fetch(&range, &Params::new())
+            .await
+            .unwrap();
+        let levels: Vec<&str> = out.logs.iter().map(|l| l.level.as_str()).collect();
+        assert_eq!(levels, vec!["ERROR", "WARN"]);
+        assert_eq!(
+            out.next_cursor,
+            Some(format!(
+                "1760000000000000200:{}",
+                LokiCursor::line_hash("WARN retrying")
+            ))
+        );
+        assert_eq!(out.container, "payments-service");
+    }
+
+    #[tokio::test]
+    async fn test_loki_pages_through_entries_sharing_a_timestamp() {
+        let server = MockServer::start().await;
+        let to = "1760054400000000000"; // 2025-10-10T00:00:00Z
+        let values = |rows: &[(&str, &str)]| {
+            serde_json::json!({
+                "status": "success",
+                "data": { "resultType": "streams", "result": [
+                    { "stream": { "app": "payments-service" }, "values": rows }
+                ]}
+            })
+        };
+        // halaman 1: Loki memotong di tengah tiga baris ber-ns sama
+        Mock::given(method("GET"))
+            .and(path("/loki/api/v1/query_range"))
+            .and(query_param("end", to))
+            .and(query_param("limit", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(values(&[
+                ("1760000000000000300", "ERROR b"),
+                ("1760000000000000300", "ERROR a"),
+            ])))
+            .mount(&server)
+            .await;
+        // halaman 2: timestamp cursor diminta lagi (end = ns + 1)
+        Mock::given(method("GET"))
+            .and(path("/loki/api/v1/query_range"))
+            .and(query_param("end", "1760000000000000301"))
+            .and(query_param("limit", "4"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(values(&[
+                ("1760000000000000300", "ERROR a"),
+                ("1760000000000000300", "ERROR b"),
+                ("1760000000000000300", "ERROR c"),
+                ("1760000000000000200", "WARN d"),
+            ])))
+            .mount(&server)
+            .await;
+
+        let range: Range = serde_json::from_value(serde_json::json!({
+            "service": "payments", "limit": 2,
+            "date_from": "2025-10-09T00:00:00Z", "date_to": "2025-10-10T00:00:00Z"
+        }))
+        .unwrap();
+        let l = loki(server.uri());
+        let first = l.fetch(&range, &Params::new()).await.unwrap();
+        let cursor = first.next_cursor.clone().unwrap();
+        let params = Params::from([("cursor".to_string(), cursor)]);
+        let second = l.fetch(&range, &params).await.unwrap();
+
+        let messages: Vec<&str> = first
+            .logs
+            .iter()
+            .chain(&second.logs)
+            .map(|l| l.message.as_str())
+            .collect();
+        assert_eq!(messages, vec!["ERROR b", "ERROR a", "ERROR c", "WARN d"]);
+        assert!(second
+            .next_cursor
+            .unwrap()
+            .starts_with("1760000000000000200:"));
+    }
+
+    #[tokio::test]
+    async fn test_opensearch_uses_index_fields_and_search_after() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/logs-payments-*/_search"))
+            .and(body_partial_json(serde_json::json!({
+                "size": 2,
+                "search_after": [1760000000300i64, "x9"],
+                "sort": [{ "ts": { "order": "desc" } }, { "_id": { "order": "desc" } }]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "hits": { "hits": [
+                    { "_source": { "ts": "2025-10-09T01:00:00Z", "lvl": "error",
+                                   "log": { "msg": "payment timeout" } },
+                      "sort": [1759971600000i64, "a1"] },
+                    { "_source": { "ts": "2025-10-09T00:59:00Z", "log": { "msg": "WARN retry" } },
+                      "sort": [1759971540000i64, "b2"] }
+                ]}
+            })))
+            .mount(&server)
+            .await;
+
+        let cfg: Backend = toml::from_str(&format!(
+            r#"
+            backend = "opensearch"
+            base_url = "{}"
+            [[indices]]
+            pattern = "logs-payments-*"
+            services = ["payment"]
+            fields = {{ timestamp = "ts", service = "svc", level = "lvl", message = "log.msg" }}
+            [[indices]]
+            pattern = "logs-*"
+            "#,
+            server.uri()
+        ))
+        .unwrap();
+        let mut errs = vec![];
+        cfg.validate(&mut errs);
+        assert!(errs.is_empty(), "{errs:?}");
+
+        let range: Range = serde_json::from_value(serde_json::json!({
+            "service": "payments", "limit": 2,
+            "date_from": "2025-10-09", "date_to": "2025-10-09"
+        }))
+        .unwrap();
+        let params = Params::from([("cursor".to_string(), r#"[1760000000300,"x9"]"#.to_string())]);
+        let out = cfg
+            .build(&ServiceCatalog::default())
+            .fetch(&range, &params)
+            .await
+            .unwrap();
+        assert_eq!(out.logs[0].level, "ERROR");
+        assert_eq!(out.logs[0].ts, "2025-10-09T09:00:00+08:00");
+        assert_eq!(out.logs[1].level, "WARN");
+        assert_eq!(out.logs[1].message, "WARN retry");
+        assert_eq!(out.next_cursor.as_deref(), Some(r#"[1759971540000,"b2"]"#));
+    }
+
+    #[tokio::test]
+    async fn test_opensearch_pages_through_hits_sharing_a_timestamp() {
+        let server = MockServer::start().await;
+        let hit = |id: &str, ms: i64, msg: &str| serde_json::json!({ "_source": { "message": msg }, "sort": [ms, id] });
+        // halaman 2: lanjut dari (ts, _id) hit terakhir, bukan hanya ts
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({
+                "search_after": [1759971600000i64, "b"]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "hits": { "hits": [hit("c", 1759971600000, "third"), hit("d", 1759971500000, "fourth")] }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "hits": { "hits": [hit("a", 1759971700000, "first"), hit("b", 1759971600000, "second")] }
+            })))
+            .mount(&server)
+            .await;
+
+        let cfg: Backend = toml::from_str(&format!(
+            r#"
+            backend = "opensearch"
+            base_url = "{}"
+            [[indices]]
+            pattern = "logs-*"
+            "#,
+            server.uri()
+        ))
+        .unwrap();
+        let adapter = cfg.build(&ServiceCatalog::default());
+        let range: Range = serde_json::from_value(serde_json::json!({
+            "service": "payments", "limit": 2,
+            "date_from": "2025-10-09", "date_to": "2025-10-09"
+        }))
+        .unwrap();
+
+        let first = adapter.fetch(&range, &Params::new()).await.unwrap();
+        let cursor = first.next_cursor.clone().unwrap();
+        let params = Params::from([("cursor".to_string(), cursor)]);
+        let second = adapter.fetch(&range, &params).await.unwrap();
+
+        let messages: Vec<_> = first
+            .logs
+            .iter()
+            .chain(&second.logs)
+            .map(|l| l.message.as_str())
+            .collect();
+        assert_eq!(messages, ["first", "second", "third", "fourth"]);
+
+        let bad = Params::from([("cursor".to_string(), "1759971600000".to_string())]);
+        assert!(matches!(
+            adapter.fetch(&range, &bad).await,
+            Err(AdapterError::BadParams(_))
+        ));
+    }
+}
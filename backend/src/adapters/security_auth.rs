@@ -0,0 +1,728 @@
+// backend/src/adapters/security_auth.rs
+
+use chrono::{DateTime, Utc};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::MySqlPool;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::time::Duration;
+
+use super::{range_schema, time_bounds, upstream_body, Adapter, AdapterError, AdapterInfo, Params};
+use crate::router::Range;
+use crate::services::ServiceCatalog;
+use crate::util::now_gmt8;
+
+#[derive(Debug, Serialize)]
+pub struct SecurityAuth {
+    pub adapter: &'static str,
+    /// synthetic | events | loki
+    pub source: &'static str,
+    pub service: String,
+    pub from: String,
+    pub to: String,
+    pub checked_at: String,
+    /// Level masking PII yang diterapkan untuk pemanggil
+    pub masking: MaskLevel,
+    pub attempts: usize,
+    pub failures: usize,
+    /// failures / attempts dalam persen
+    pub failed_login_rate: f64,
+    pub lockouts: usize,
+    pub top_ips: Vec<Offender>,
+    pub top_users: Vec<Offender>,
+    /// IP yang gagal login ke banyak akun berbeda
+    pub credential_stuffing: Vec<StuffingSuspect>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Offender {
+    /// IP atau username (sudah dimasking sesuai role)
+    pub key: String,
+    pub failures: usize,
+    pub lockouts: usize,
+    /// Jumlah user berbeda (untuk IP) atau IP berbeda (untuk user)
+    pub distinct: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StuffingSuspect {
+    pub ip: String,
+    pub distinct_users: usize,
+    pub failures: usize,
+    /// Ada login sukses dari IP ini setelah rentetan gagal
+    pub success_after_failures: bool,
+}
+
+/// Satu event autentikasi (dari tabel atau log)
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AuthEvent {
+    pub occurred_at: DateTime<Utc>,
+    pub username: Option<String>,
+    pub ip: Option<String>,
+    /// success | failure | lockout
+    pub outcome: String,
+}
+
+/* ------------------------- Masking ------------------------- */
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaskLevel {
+    /// Tampilkan apa adanya
+    None,
+    /// jo***@example.com, 203.0.113.x
+    Partial,
+    /// Pseudonim stabil (user-3f2a9c, ip-81d0e4)
+    #[default]
+    Full,
+}
+
+/// `[pii_masking]`: level per role (users.role, di-lookup pipeline dari user_id).
+/// Pemanggil tanpa role terpercaya selalu mendapat `Full`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Masking {
+    pub roles: BTreeMap<String, MaskLevel>,
+}
+
+impl Default for Masking {
+    fn default() -> Self {
+        Self {
+            roles: BTreeMap::from([
+                ("admin".into(), MaskLevel::None),
+                ("engineer".into(), MaskLevel::Partial),
+                ("viewer".into(), MaskLevel::Full),
+            ]),
+        }
+    }
+}
+
+impl Masking {
+    /// Role mengikuti enum users.role
+    pub fn validate(&self, errs: &mut Vec<String>) {
+        for role in self.roles.keys() {
+            if !["admin", "engineer", "viewer"].contains(&role.as_str()) {
+                errs.push(format!(
+                    "pii_masking.roles.{role}: unknown role (admin, engineer, viewer)"
+                ));
+            }
+        }
+    }
+
+    /// `role` harus berasal dari lookup server ([`crate::fetch::trusted_role`]), bukan input klien
+    pub fn level_for(&self, role: Option<&str>) -> MaskLevel {
+        role.and_then(|r| self.roles.get(r))
+            .copied()
+            .unwrap_or(MaskLevel::Full)
+    }
+}
+
+fn pseudonym(prefix: &str, v: &str) -> String {
+    format!(
+        "{prefix}-{}",
+        &hex::encode(Sha256::digest(v.as_bytes()))[..6]
+    )
+}
+
+pub fn mask_user(v: &str, level: MaskLevel) -> String {
+    match level {
+        MaskLevel::None => v.to_string(),
+        MaskLevel::Full => pseudonym("user", v),
+        MaskLevel::Partial => {
+            let (local, domain) = v.split_once('@').map_or((v, None), |(l, d)| (l, Some(d)));
+            let head: String = local.chars().take(2).collect();
+            match domain {
+                Some(d) => format!("{head}***@{d}"),
+                None => format!("{head}***"),
+            }
+        }
+    }
+}
+
+pub fn mask_ip(v: &str, level: MaskLevel) -> String {
+    match level {
+        MaskLevel::None => v.to_string(),
+        MaskLevel::Full => pseudonym("ip", v),
+        MaskLevel::Partial => match v.parse::<std::net::IpAddr>() {
+            Ok(std::net::IpAddr::V4(ip)) => {
+                let o = ip.octets();
+                format!("{}.{}.{}.x", o[0], o[1], o[2])
+            }
+            Ok(std::net::IpAddr::V6(ip)) => {
+                let s = ip.segments();
+                format!("{:x}:{:x}:{:x}::x", s[0], s[1], s[2])
+            }
+            Err(_) => pseudonym("ip", v),
+        },
+    }
+}
+
+impl SecurityAuth {
+    /// Terapkan masking ke semua IP/username di output
+    pub fn mask(&mut self, level: MaskLevel) {
+        self.masking = level;
+        for o in &mut self.top_ips {
+            o.key = mask_ip(&o.key, level);
+        }
+        for o in &mut self.top_users {
+            o.key = mask_user(&o.key, level);
+        }
+        for s in &mut self.credential_stuffing {
+            s.ip = mask_ip(&s.ip, level);
+        }
+    }
+}
+
+/* ------------------------- Analisis ------------------------- */
+
+#[derive(Default)]
+struct Tally {
+    failures: usize,
+    lockouts: usize,
+    others: BTreeSet<String>,
+    last_failure: Option<DateTime<Utc>>,
+    success_after_failures: bool,
+}
+
+/// Hitung statistik dari event mentah (belum dimasking; `masking` = None)
+#[allow(clippy::too_many_arguments)]
+fn analyze(
+    source: &'static str,
+    service: String,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    mut events: Vec<AuthEvent>,
+    top: usize,
+    stuffing_min_users: usize,
+) -> SecurityAuth {
+    events.sort_by_key(|e| e.occurred_at);
+    let mut by_ip: HashMap<String, Tally> = HashMap::new();
+    let mut by_user: HashMap<String, Tally> = HashMap::new();
+    let (mut attempts, mut failures, mut lockouts) = (0, 0, 0);
+
+    for e in &events {
+        let ip = e.ip.clone().unwrap_or_else(|| "unknown".into());
+        let user = e.username.clone().unwrap_or_else(|| "unknown".into());
+        match e.outcome.as_str() {
+            "success" => {
+                attempts += 1;
+                let t = by_ip.entry(ip).or_default();
+                if t.last_failure.is_some() {
+                    t.success_after_failures = true;
+                }
+            }
+            "failure" => {
+                attempts += 1;
+                failures += 1;
+                let t = by_ip.entry(ip.clone()).or_default();
+                t.failures += 1;
+                t.others.insert(user.clone());
+                t.last_failure = Some(e.occurred_at);
+                let u = by_user.entry(user).or_default();
+                u.failures += 1;
+                u.others.insert(ip);
+            }
+            "lockout" => {
+                lockouts += 1;
+                by_ip.entry(ip.clone()).or_default().lockouts += 1;
+                let u = by_user.entry(user).or_default();
+                u.lockouts += 1;
+                u.others.insert(ip);
+            }
+            _ => {}
+        }
+    }
+
+    let ranked = |m: &HashMap<String, Tally>| {
+        let mut v: Vec<Offender> = m
+            .iter()
+            .filter(|(_, t)| t.failures + t.lockouts > 0)
+            .map(|(k, t)| Offender {
+                key: k.clone(),
+                failures: t.failures,
+                lockouts: t.lockouts,
+                distinct: t.others.len(),
+            })
+            .collect();
+        v.sort_by(|a, b| (b.failures, b.lockouts, &a.key).cmp(&(a.failures, a.lockouts, &b.key)));
+        v.truncate(top);
+        v
+    };
+    let mut credential_stuffing: Vec<StuffingSuspect> = by_ip
+        .iter()
+        .filter(|(_, t)| t.others.len() >= stuffing_min_users)
+        .map(|(ip, t)| StuffingSuspect {
+            ip: ip.clone(),
+            distinct_users: t.others.len(),
+            failures: t.failures,
+            success_after_failures: t.success_after_failures,
+        })
+        .collect();
+    credential_stuffing.sort_by(|a, b| {
+        b.distinct_users
+            .cmp(&a.distinct_users)
+            .then(a.ip.cmp(&b.ip))
+    });
+
+    SecurityAuth {
+        adapter: "security_auth",
+        source,
+        service,
+        from: from.to_rfc3339(),
+        to: to.to_rfc3339(),
+        checked_at: now_gmt8().to_rfc3339(),
+        masking: MaskLevel::None,
+        attempts,
+        failures,
+        failed_login_rate: if attempts > 0 {
+            (failures as f64 * 10000.0 / attempts as f64).round() / 100.0
+        } else {
+            0.0
+        },
+        lockouts,
+        top_ips: ranked(&by_ip),
+        top_users: ranked(&by_user),
+        credential_stuffing,
+    }
+}
+
+/* ------------------------- Config ------------------------- */
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum Backend {
+    #[default]
+    Synthetic,
+    /// Tabel auth_events di database aplikasi
+    Events(Analysis),
+    Loki(LokiAuthConfig),
+}
+
+/// Ambang analisis, dipakai semua backend
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Analysis {
+    /// Jumlah IP/user teratas
+    pub top: usize,
+    /// IP gagal ke ≥ n akun berbeda dianggap credential stuffing
+    pub stuffing_min_users: usize,
+    pub max_events: usize,
+}
+
+impl Default for Analysis {
+    fn default() -> Self {
+        Self {
+            top: 10,
+            stuffing_min_users: 10,
+            max_events: 50_000,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LokiAuthConfig {
+    pub base_url: String,
+    pub tenant: Option<String>,
+    /// Bisa lewat env LOKI_TOKEN
+    #[serde(default)]
+    pub token: String,
+    /// Selector stream event auth (baris JSON), mis. {app="auth-service", stream="audit"}
+    pub selector: String,
+    /// Nama field di baris JSON
+    #[serde(default)]
+    pub fields: AuthFields,
+    #[serde(default)]
+    pub analysis: Analysis,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthFields {
+    pub username: String,
+    pub ip: String,
+    pub outcome: String,
+}
+
+impl Default for AuthFields {
+    fn default() -> Self {
+        Self {
+            username: "username".into(),
+            ip: "ip".into(),
+            outcome: "outcome".into(),
+        }
+    }
+}
+
+impl Backend {
+    pub fn validate(&self, errs: &mut Vec<String>) {
+        let analysis = match self {
+            Backend::Synthetic => return,
+            Backend::Events(a) => a,
+            Backend::Loki(l) => {
+                if !(l.base_url.starts_with("http://") || l.base_url.starts_with("https://")) {
+                    errs.push(
+                        "adapters.security_auth.base_url must start with http:// or https://"
+                            .into(),
+                    );
+                }
+                if !l.selector.trim_start().starts_with('{') {
+                    errs.push(
+                        "adapters.security_auth.selector must be a LogQL stream selector".into(),
+                    );
+                }
+                &l.analysis
+            }
+        };
+        if analysis.top == 0 || analysis.stuffing_min_users < 2 || analysis.max_events == 0 {
+            errs.push(
+                "adapters.security_auth: top and max_events must be positive, stuffing_min_users ≥ 2"
+                    .into(),
+            );
+        }
+    }
+
+    pub fn build(
+        &self,
+        services: &ServiceCatalog,
+        pool: &MySqlPool,
+    ) -> Box<dyn Adapter<Output = SecurityAuth>> {
+        match self {
+            Backend::Synthetic => Box::new(Synthetic),
+            Backend::Events(a) => Box::new(Events {
+                analysis: a.clone(),
+                services: services.clone(),
+                pool: pool.clone(),
+            }),
+            Backend::Loki(l) => Box::new(Loki::new(l.clone())),
+        }
+    }
+}
+
+fn params_schema() -> serde_json::Value {
+    range_schema(serde_json::json!({}))
+}
+
+/* ------------------------- Synthetic ------------------------- */
+
+/// 2000 event: 47 login gagal dari 1999 percobaan (2.35%), 1 lockout, satu IP mencoba 12 akun
+pub struct Synthetic;
+
+impl Adapter for Synthetic {
+    type Output = SecurityAuth;
+
+    fn describe(&self) -> AdapterInfo {
+        AdapterInfo {
+            domain: "security_auth",
+            endpoint: "/api/security-auth",
+            backend: "synthetic",
+            description: "Login gagal, lockout dan pola serangan autentikasi",
+        }
+    }
+
+    fn params_schema(&self) -> serde_json::Value {
+        params_schema()
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        q: &'a Range,
+        _params: &'a Params,
+    ) -> BoxFuture<'a, Result<SecurityAuth, AdapterError>> {
+        Box::pin(async move {
+            let (from, to) = time_bounds(q)?;
+            let step = (to - from) / 2000;
+            let events = (0..2000)
+                .map(|i| {
+                    let (username, ip, outcome) = match i {
+                        // credential stuffing: 1 IP, 12 akun
+                        0..=11 => (format!("user{i}@smrt.com.sg"), "198.51.100.23", "failure"),
+                        12 => ("user3@smrt.com.sg".into(), "198.51.100.23", "success"),
+                        13..=47 => ("ops.admin@smrt.com.sg".into(), "203.0.113.9", "failure"),
+                        48 => ("ops.admin@smrt.com.sg".into(), "203.0.113.9", "lockout"),
+                        _ => (
+                            format!("user{}@smrt.com.sg", i % 300),
+                            "10.20.0.5",
+                            "success",
+                        ),
+                    };
+                    AuthEvent {
+                        occurred_at: from + step * i,
+                        username: Some(username),
+                        ip: Some(ip.into()),
+                        outcome: outcome.into(),
+                    }
+                })
+                .collect();
+            let service = q
+                .service
+                .clone()
+                .unwrap_or_else(|| "api-gateway-production".into());
+            Ok(analyze("synthetic", service, from, to, events, 10, 10))
+        })
+    }
+}
+
+/* ------------------------- Events table ------------------------- */
+
+/// Backend tabel auth_events
+pub struct Events {
+    analysis: Analysis,
+    services: ServiceCatalog,
+    pool: MySqlPool,
+}
+
+impl Adapter for Events {
+    type Output = SecurityAuth;
+
+    fn describe(&self) -> AdapterInfo {
+        AdapterInfo {
+            domain: "security_auth",
+            endpoint: "/api/security-auth",
+            backend: "events",
+            description: "Failed-login rate, lockout, IP/user teratas dan credential stuffing dari tabel auth_events",
+        }
+    }
+
+    fn params_schema(&self) -> serde_json::Value {
+        params_schema()
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        q: &'a Range,
+        _params: &'a Params,
+    ) -> BoxFuture<'a, Result<SecurityAuth, AdapterError>> {
+        Box::pin(async move {
+            let (from, to) = time_bounds(q)?;
+            let mut qb = sqlx::QueryBuilder::new(
+                "SELECT occurred_at, username, ip, outcome FROM auth_events WHERE occurred_at >= ",
+            );
+            qb.push_bind(from).push(" AND occurred_at < ").push_bind(to);
+            let service = q.service.clone().filter(|s| !s.trim().is_empty());
+            if let Some(s) = &service {
+                let mut names = vec![s.clone(), self.services.container(s)];
+                names.extend(self.services.lookup(s).map(|(n, _)| n.to_string()));
+                qb.push(" AND service IN (");
+                let mut sep = qb.separated(", ");
+                for n in names {
+                    sep.push_bind(n);
+                }
+                qb.push(")");
+            }
+            qb.push(" ORDER BY occurred_at LIMIT ")
+                .push_bind(self.analysis.max_events as i64);
+            let events: Vec<AuthEvent> = qb
+                .build_query_as()
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| AdapterError::Upstream(format!("auth_events: {e}")))?;
+            Ok(analyze(
+                "events",
+                service.unwrap_or_else(|| "all".into()),
+                from,
+                to,
+                events,
+                self.analysis.top,
+                self.analysis.stuffing_min_users,
+            ))
+        })
+    }
+}
+
+/* ------------------------- Loki ------------------------- */
+
+/// Backend log: baris JSON event auth dari Loki
+pub struct Loki {
+    cfg: LokiAuthConfig,
+    client: reqwest::Client,
+}
+
+impl Loki {
+    pub fn new(cfg: LokiAuthConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .unwrap_or_default();
+        Self { cfg, client }
+    }
+
+    /// Baris JSON → AuthEvent; outcome dinormalisasi (fail/failed → failure, dst.)
+    fn parse(&self, ns: &str, line: &str) -> Option<AuthEvent> {
+        let v: serde_json::Value = serde_json::from_str(line).ok()?;
+        let field = |k: &str| v.get(k).and_then(|x| x.as_str()).map(str::to_string);
+        let outcome = match field(&self.cfg.fields.outcome)?.to_lowercase().as_str() {
+            "success" | "ok" | "succeeded" => "success",
+            "failure" | "fail" | "failed" | "denied" => "failure",
+            "lockout" | "locked" | "locked_out" => "lockout",
+            _ => return None,
+        };
+        Some(AuthEvent {
+            occurred_at: DateTime::from_timestamp_nanos(ns.parse().ok()?),
+            username: field(&self.cfg.fields.username),
+            ip: field(&self.cfg.fields.ip),
+            outcome: outcome.into(),
+        })
+    }
+}
+
+impl Adapter for Loki {
+    type Output = SecurityAuth;
+
+    fn describe(&self) -> AdapterInfo {
+        AdapterInfo {
+            domain: "security_auth",
+            endpoint: "/api/security-auth",
+            backend: "loki",
+            description: "Failed-login rate, lockout, IP/user teratas dan credential stuffing dari log auth di Loki",
+        }
+    }
+
+    fn params_schema(&self) -> serde_json::Value {
+        params_schema()
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        q: &'a Range,
+        _params: &'a Params,
+    ) -> BoxFuture<'a, Result<SecurityAuth, AdapterError>> {
+        Box::pin(async move {
+            let (from, to) = time_bounds(q)?;
+            let url = format!(
+                "{}/loki/api/v1/query_range",
+                self.cfg.base_url.trim_end_matches('/')
+            );
+            let mut req = self.client.get(&url).query(&[
+                ("query", self.cfg.selector.clone()),
+                (
+                    "start",
+                    from.timestamp_nanos_opt().unwrap_or_default().to_string(),
+                ),
+                (
+                    "end",
+                    to.timestamp_nanos_opt().unwrap_or_default().to_string(),
+                ),
+                ("limit", self.cfg.analysis.max_events.to_string()),
+                ("direction", "forward".into()),
+            ]);
+            if let Some(t) = &self.cfg.tenant {
+                req = req.header("X-Scope-OrgID", t);
+            }
+            if !self.cfg.token.is_empty() {
+                req = req.bearer_auth(&self.cfg.token);
+            }
+            let resp = req
+                .send()
+                .await
+                .map_err(|e| AdapterError::Upstream(format!("loki: {e}")))?;
+            let status = resp.status();
+            if !status.is_success() {
+                let body = resp.text().await.unwrap_or_default();
+                let body = upstream_body(&body);
+                return Err(AdapterError::Upstream(format!("loki → {status}: {body}")));
+            }
+            let body: serde_json::Value = resp
+                .json()
+                .await
+                .map_err(|e| AdapterError::Upstream(format!("loki: unexpected response: {e}")))?;
+            let events = body["data"]["result"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .flat_map(|s| s["values"].as_array().into_iter().flatten())
+                .filter_map(|v| self.parse(v[0].as_str()?, v[1].as_str()?))
+                .collect();
+            let service = q
+                .service
+                .clone()
+                .unwrap_or_else(|| self.cfg.selector.clone());
+            Ok(analyze(
+                "loki",
+                service,
+                from,
+                to,
+                events,
+                self.cfg.analysis.top,
+                self.cfg.analysis.stuffing_min_users,
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_synthetic_rates_stuffing_and_masking() {
+        let range: Range = serde_json::from_value(serde_json::json!({})).unwrap();
+        let mut out = Synthetic.fetch(&range, &Params::new()).await.unwrap();
+        assert_eq!((out.attempts, out.failures, out.lockouts), (1999, 47, 1));
+        assert_eq!(out.top_users[0].key, "ops.admin@smrt.com.sg");
+        assert_eq!(out.credential_stuffing.len(), 1);
+        assert!(out.credential_stuffing[0].success_after_failures);
+
+        out.mask(Masking::default().level_for(Some("engineer")));
+        assert_eq!(out.top_users[0].key, "op***@smrt.com.sg");
+        assert_eq!(out.credential_stuffing[0].ip, "198.51.100.x");
+        assert_eq!(Masking::default().level_for(None), MaskLevel::Full);
+        assert_eq!(
+            mask_ip("203.0.113.9", MaskLevel::Full),
+            mask_ip("203.0.113.9", MaskLevel::Full)
+        );
+        assert!(mask_user("ops.admin@smrt.com.sg", MaskLevel::Full).starts_with("user-"));
+    }
+
+    #[tokio::test]
+    async fn test_loki_parses_json_lines_with_custom_fields() {
+        let server = MockServer::start().await;
+        let line = |user: &str, ip: &str, result: &str| {
+            serde_json::json!({ "user": user, "client_ip": ip, "result": result }).to_string()
+        };
+        Mock::given(method("GET"))
+            .and(path("/loki/api/v1/query_range"))
+            .and(query_param("query", r#"{app="auth-service"}"#))
+            .and(query_param("direction", "forward"))
+            .and(header("X-Scope-OrgID", "smrt"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "status": "success",
+                "data": { "resultType": "streams", "result": [{
+                    "stream": { "app": "auth-service" },
+                    "values": [
+                        ["1760860800000000000", line("alice", "2001:db8::1", "FAILED")],
+                        ["1760860801000000000", line("alice", "2001:db8::1", "locked_out")],
+                        ["1760860802000000000", line("bob", "10.0.0.1", "ok")],
+                        ["1760860803000000000", "not json"],
+                    ],
+                }]},
+            })))
+            .mount(&server)
+            .await;
+
+        let loki = Loki::new(LokiAuthConfig {
+            base_url: server.uri(),
+            tenant: Some("smrt".into()),
+            token: String::new(),
+            selector: r#"{app="auth-service"}"#.into(),
+            fields: AuthFields {
+                username: "user".into(),
+                ip: "client_ip".into(),
+                outcome: "result".into(),
+            },
+            analysis: Analysis::default(),
+        });
+        let range: Range = serde_json::from_value(serde_json::json!({})).unwrap();
+        let mut out = loki.fetch(&range, &Params::new()).await.unwrap();
+        assert_eq!((out.attempts, out.failures, out.lockouts), (2, 1, 1));
+        assert_eq!(out.failed_login_rate, 50.0);
+        assert_eq!(out.top_ips[0].key, "2001:db8::1");
+
+        out.mask(MaskLevel::Partial);
+        assert_eq!(out.top_ips[0].key, "2001:db8:0::x");
+        assert_eq!(out.top_users[0].key, "al***");
+    }
+}
@@ -0,0 +1,48 @@
+// backend/src/adapters/user_feedback.rs
+
+use futures_util::future::BoxFuture;
+use serde::Serialize;
+
+use super::{range_schema, Adapter, AdapterError, AdapterInfo, Params};
+use crate::router::Range;
+use crate::util::now_gmt8;
+
+#[derive(Debug, Serialize)]
+pub struct UserFeedback {
+    pub adapter: &'static str,
+    pub source: String,
+    pub checked_at: String,
+}
+
+pub struct Synthetic;
+
+impl Adapter for Synthetic {
+    type Output = UserFeedback;
+
+    fn describe(&self) -> AdapterInfo {
+        AdapterInfo {
+            domain: "user_feedback",
+            endpoint: "/api/user-feedback",
+            backend: "synthetic",
+            description: "Ulasan & feedback pengguna (store, form)",
+        }
+    }
+
+    fn params_schema(&self) -> serde_json::Value {
+        range_schema(serde_json::json!({}))
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        _range: &'a Range,
+        _params: &'a Params,
+    ) -> BoxFuture<'a, Result<UserFeedback, AdapterError>> {
+        Box::pin(async move {
+            Ok(UserFeedback {
+                adapter: "user_feedback",
+                source: "playstore".into(),
+                checked_at: now_gmt8().to_rfc3339(),
+            })
+        })
+    }
+}
@@ -0,0 +1,158 @@
+// backend/src/answer.rs
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Info,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
+/// Satu temuan; `endpoint` = hasil join yang mendukung klaim ini
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Finding {
+    pub claim: String,
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub severity: Severity,
+    /// Kutipan singkat dari data (mis. baris log / angka metrik)
+    #[serde(default)]
+    pub evidence: Option<String>,
+}
+
+/// Jawaban terstruktur (opsi `structured` di ChatRequest)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StructuredAnswer {
+    pub summary: String,
+    #[serde(default)]
+    pub severity: Severity,
+    #[serde(default)]
+    pub findings: Vec<Finding>,
+    #[serde(default)]
+    pub next_actions: Vec<String>,
+}
+
+/// Instruksi tambahan untuk langkah answer; `endpoints` = endpoint yang di-join
+pub fn instructions(endpoints: &[String]) -> String {
+    format!(
+        r#"Respond with ONE JSON object only, no prose, using this shape:
+{{"summary": string, "severity": "info"|"low"|"medium"|"high"|"critical",
+  "findings": [{{"claim": string, "endpoint": string, "severity": same enum, "evidence": string}}],
+  "next_actions": [string]}}
+Every finding MUST cite the endpoint whose data supports it, chosen from: {}.
+Quote the supporting value or log line in "evidence". Keep 1-6 findings and 1-4 next actions."#,
+        serde_json::to_string(endpoints).unwrap_or_else(|_| "[]".into())
+    )
+}
+
+/// Parse output model. Sitasi ke endpoint yang tidak ada di hasil join dibuang
+/// (endpoint = None) supaya frontend tidak menautkan klaim ke data yang salah.
+pub fn parse(content: &str, endpoints: &[String]) -> Option<StructuredAnswer> {
+    let mut ans: StructuredAnswer = serde_json::from_str(content.trim()).ok()?;
+    if ans.summary.trim().is_empty() {
+        return None;
+    }
+    for f in &mut ans.findings {
+        if f.endpoint
+            .as_ref()
+            .is_some_and(|ep| !endpoints.contains(ep))
+        {
+            tracing::warn!(endpoint = ?f.endpoint, "finding cites endpoint outside joined results");
+            f.endpoint = None;
+        }
+    }
+    Some(ans)
+}
+
+impl StructuredAnswer {
+    /// Versi teks (untuk `reply`, chat_logs & history session)
+    pub fn render(&self) -> String {
+        let sev = |s: Severity| s.as_str().to_uppercase();
+        let mut out = format!("[{}] {}\n", sev(self.severity), self.summary.trim());
+        if !self.findings.is_empty() {
+            out.push_str("\nFindings:\n");
+            for (i, f) in self.findings.iter().enumerate() {
+                out.push_str(&format!(
+                    "{}. [{}] {} ({})\n",
+                    i + 1,
+                    sev(f.severity),
+                    f.claim.trim(),
+                    f.endpoint.as_deref().unwrap_or("uncited")
+                ));
+            }
+        }
+        if !self.next_actions.is_empty() {
+            out.push_str("\nNext actions:\n");
+            for a in &self.next_actions {
+                out.push_str(&format!("- {}\n", a.trim()));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_drops_citations_outside_joined_results() {
+        let eps = vec!["/api/runtime-logs".to_string()];
+        let raw = r#"{
+            "summary": "payments is throwing 5xx",
+            "severity": "high",
+            "findings": [
+                {"claim": "12 ERROR lines", "endpoint": "/api/runtime-logs", "severity": "high", "evidence": "ERROR timeout"},
+                {"claim": "p95 up", "endpoint": "/api/observability"}
+            ],
+            "next_actions": ["check upstream gateway"]
+        }"#;
+        let ans = parse(raw, &eps).unwrap();
+        assert_eq!(ans.severity, Severity::High);
+        assert_eq!(
+            ans.findings[0].endpoint.as_deref(),
+            Some("/api/runtime-logs")
+        );
+        assert_eq!(ans.findings[1].endpoint, None);
+        assert_eq!(ans.findings[1].severity, Severity::Info);
+
+        assert!(parse("not json", &eps).is_none());
+        assert!(parse(r#"{"summary": "  "}"#, &eps).is_none());
+    }
+
+    #[test]
+    fn test_render_lists_findings_with_citation() {
+        let ans = StructuredAnswer {
+            summary: "all good".into(),
+            severity: Severity::Low,
+            findings: vec![Finding {
+                claim: "no errors".into(),
+                endpoint: Some("/api/runtime-logs".into()),
+                severity: Severity::Info,
+                evidence: None,
+            }],
+            next_actions: vec!["nothing".into()],
+        };
+        let text = ans.render();
+        assert!(text.starts_with("[LOW] all good"));
+        assert!(text.contains("1. [INFO] no errors (/api/runtime-logs)"));
+        assert!(text.contains("- nothing"));
+    }
+}
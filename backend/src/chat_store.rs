@@ -0,0 +1,120 @@
+// backend/src/chat_store.rs
+
+use serde_json::Value;
+use sqlx::MySqlPool;
+use std::collections::HashMap;
+
+use crate::mcp::RouterPlan;
+
+/// Batas aman untuk kolom TEXT (64 KiB)
+const SUMMARY_MAX_BYTES: usize = 65_000;
+
+pub(crate) fn truncate_utf8(s: &str, max: usize) -> &str {
+    if s.len() <= max {
+        return s;
+    }
+    let mut end = max;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+/// Catat pertanyaan + plan; return id chat_logs (None kalau DB gagal).
+/// Semua fungsi di sini best-effort: error DB hanya di-log, chat tetap jalan.
+pub async fn start(
+    pool: &MySqlPool,
+    request_id: &str,
+    session_id: Option<&str>,
+    user_id: Option<&str>,
+    user_query: &str,
+    plan: &RouterPlan,
+) -> Option<i64> {
+    let res = sqlx::query(
+        "INSERT INTO chat_logs (request_id, session_id, user_id, user_query, detected_intent, routed_endpoints)
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(request_id)
+    .bind(session_id)
+    .bind(user_id)
+    .bind(user_query)
+    .bind(&plan.intent)
+    .bind(serde_json::json!(plan.endpoints))
+    .execute(pool)
+    .await;
+
+    match res {
+        Ok(r) => Some(r.last_insert_id() as i64),
+        Err(e) => {
+            tracing::warn!(%request_id, "failed to insert chat_log: {e}");
+            None
+        }
+    }
+}
+
+pub async fn save_api_result(
+    pool: &MySqlPool,
+    chat_log_id: Option<i64>,
+    endpoint: &str,
+    params: &HashMap<String, String>,
+    data: &Value,
+) {
+    let Some(id) = chat_log_id else { return };
+    let status = if data.get("error").is_some() {
+        "error"
+    } else {
+        "ok"
+    };
+
+    let res = sqlx::query(
+        "INSERT INTO api_results (chat_log_id, endpoint, status, request_params, response_data)
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(id)
+    .bind(endpoint)
+    .bind(status)
+    .bind(serde_json::json!(params))
+    .bind(data)
+    .execute(pool)
+    .await;
+
+    if let Err(e) = res {
+        tracing::warn!(chat_log_id = id, %endpoint, "failed to insert api_result: {e}");
+    }
+}
+
+/// Simpan jawaban akhir dan tautkan llm_usage (dicatat per request_id) ke chat_log ini
+pub async fn finish(pool: &MySqlPool, chat_log_id: Option<i64>, request_id: &str, answer: &str) {
+    let Some(id) = chat_log_id else { return };
+
+    let res = sqlx::query("UPDATE chat_logs SET response_summary = ? WHERE id = ?")
+        .bind(truncate_utf8(answer, SUMMARY_MAX_BYTES))
+        .bind(id)
+        .execute(pool)
+        .await;
+    if let Err(e) = res {
+        tracing::warn!(chat_log_id = id, "failed to update chat_log answer: {e}");
+    }
+
+    let res = sqlx::query("UPDATE llm_usage SET chat_log_id = ? WHERE request_id = ?")
+        .bind(id)
+        .bind(request_id)
+        .execute(pool)
+        .await;
+    if let Err(e) = res {
+        tracing::warn!(chat_log_id = id, "failed to link llm_usage: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_utf8_respects_char_boundary() {
+        assert_eq!(truncate_utf8("hello", 10), "hello");
+        assert_eq!(truncate_utf8("hello", 3), "hel");
+        // "é" = 2 bytes; potong di tengah karakter harus mundur
+        assert_eq!(truncate_utf8("aé", 2), "a");
+    }
+}
@@ -1,17 +1,51 @@
 // src/fetch.rs
 
 use anyhow::Result;
+use axum::http::HeaderMap;
+use rand::RngCore;
 use reqwest::Client;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Role pemanggil (users.role) yang diteruskan pipeline ke endpoint internal
+pub const ROLE_HEADER: &str = "x-mcp-role";
+/// Bukti bahwa request berasal dari pipeline di proses ini, bukan dari klien
+pub const INTERNAL_TOKEN_HEADER: &str = "x-mcp-internal-token";
+
+/// Token acak per proses; tidak pernah dikirim ke klien
+fn internal_token() -> &'static str {
+    static TOKEN: OnceLock<String> = OnceLock::new();
+    TOKEN.get_or_init(|| {
+        let mut b = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut b);
+        hex::encode(b)
+    })
+}
+
+/// Role dari [`ROLE_HEADER`], hanya bila request membawa token internal yang cocok
+pub fn trusted_role(headers: &HeaderMap) -> Option<&str> {
+    let token = headers.get(INTERNAL_TOKEN_HEADER)?.to_str().ok()?;
+    if token != internal_token() {
+        return None;
+    }
+    headers.get(ROLE_HEADER)?.to_str().ok()
+}
 
 pub async fn fetch_one(
     client: &Client,
     base_url: &str,
     endpoint: &str,
     params: &HashMap<String, String>,
+    role: Option<&str>,
 ) -> Result<Value> {
     let mut req = client.get(format!("{base_url}{endpoint}"));
+    // role hasil lookup server (bukan input klien) untuk endpoint yang menyesuaikan data (masking PII)
+    if let Some(r) = role {
+        req = req
+            .header(INTERNAL_TOKEN_HEADER, internal_token())
+            .header(ROLE_HEADER, r);
+    }
     if !params.is_empty() {
         req = req.query(params);
     }
@@ -25,18 +59,10 @@ pub async fn fetch_one(
     Ok(resp.json::<Value>().await?)
 }
 
-pub async fn fetch_join(
-    client: &Client,
-    base_url: &str,
-    endpoints: &[String],
-    params: &HashMap<String, String>,
-) -> Result<Value> {
-    let mut joined = Vec::<serde_json::Value>::with_capacity(endpoints.len());
-    for ep in endpoints {
-        let data = fetch_one(client, base_url, ep, params)
-            .await
-            .unwrap_or_else(|e| serde_json::json!({ "error": e.to_string() }));
-        joined.push(serde_json::json!({ "endpoint": ep, "data": data }));
-    }
-    Ok(serde_json::json!({ "results": joined }))
+#[cfg(test)]
+pub(crate) fn internal_headers(role: &str) -> [(&'static str, String); 2] {
+    [
+        (INTERNAL_TOKEN_HEADER, internal_token().to_string()),
+        (ROLE_HEADER, role.to_string()),
+    ]
 }
@@ -0,0 +1,10 @@
+// backend/src/handlers/adapters.rs
+
+use axum::{extract::State, Json};
+
+use crate::adapters::Registry;
+
+/// GET /api/adapters — adapter aktif per domain (backend + params schema)
+pub async fn list_adapters(State(adapters): State<Registry>) -> Json<Vec<serde_json::Value>> {
+    Json(adapters.current().describe_all())
+}
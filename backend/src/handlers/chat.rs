@@ -2,21 +2,29 @@
 
 // backend/src/handlers/chat.rs
 
-use crate::config::Config;
-use crate::fetch::{fetch_join, fetch_one};
-use crate::mcp::{intent_prompt, parse_or_fallback};
+use super::internal_error;
+use crate::answer::StructuredAnswer;
+use crate::config::{Config, SharedConfig};
+use crate::mcp::{self, RouterPlan};
+use crate::pipeline::{self, build_client, ChatEvent, PendingClarification, PipelineCtx};
+use crate::sampling::SamplingOverrides;
+use crate::sessions;
+use crate::stream_buffer::{self, StreamBuffer};
+use crate::traces::TraceRecorder;
 
 use axum::{
-    extract::{Query, State},
+    extract::{Path, Query, State},
     http::HeaderMap,
     response::sse::{Event, KeepAlive, Sse},
     Json,
 };
-use futures_util::{Stream, TryStreamExt};
-use regex::Regex;
+use futures_util::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use sqlx::MySqlPool;
+use std::convert::Infallible;
+use std::sync::Arc;
 use std::time::Duration;
+use tracing::Instrument;
 
 /* ------------------------- Types ------------------------- */
 
@@ -26,597 +34,365 @@ pub struct ChatRequest {
     pub date_from: Option<String>,
     pub date_to: Option<String>,
     pub tz: Option<String>,
+    pub user_id: Option<String>,
+    pub session_id: Option<String>, // multi-turn: id dari client (mis. UUID)
+    /// Paksa daftar endpoint (menggantikan pilihan planner); hanya lewat body JSON
+    #[serde(default)]
+    pub endpoints: Option<Vec<String>>,
+    /// Nama settings profile yang diminta client
+    pub profile: Option<String>,
+    /// Minta jawaban terstruktur (summary, findings + sitasi endpoint, next actions)
+    #[serde(default)]
+    pub structured: bool,
+    /// Override sampling per-request; divalidasi terhadap bounds di settings aktif
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    pub max_tokens: Option<u32>,
+    /// Hanya lewat body JSON
+    #[serde(default)]
+    pub stop: Option<Vec<String>>,
 }
 
-#[derive(Serialize)]
-pub struct ChatResponse {
-    pub reply: String,
-}
-
-/* ------------------------- Helpers ------------------------- */
-
-fn internal_base_url(headers_opt: Option<&HeaderMap>) -> String {
-    // 1) Use ENV if present
-    if let Ok(v) = std::env::var("SELF_BASE_URL") {
-        if !v.trim().is_empty() {
-            return v;
+impl ChatRequest {
+    /// Validasi field yang tidak bisa dicek sebelum pipeline jalan
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self
+            .session_id
+            .as_deref()
+            .is_some_and(|sid| !sessions::valid_id(sid))
+        {
+            return Err("invalid session_id (use 1-64 chars of [A-Za-z0-9_-])".into());
+        }
+        if let Some(eps) = &self.endpoints {
+            if let Some(bad) = eps.iter().find(|e| !mcp::is_known_endpoint(e)) {
+                return Err(format!("unknown endpoint {bad}"));
+            }
         }
+        Ok(())
     }
-    // 2) Fallback to Host header
-    let host = headers_opt
-        .and_then(|h| h.get("host"))
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("127.0.0.1:8080");
-    format!("http://{host}")
-}
 
-fn build_client(timeout: Duration) -> Result<reqwest::Client, axum::Error> {
-    let mut b = reqwest::Client::builder()
-        .timeout(timeout)
-        .connect_timeout(Duration::from_secs(10))
-        .pool_idle_timeout(Duration::from_secs(90))
-        .tcp_keepalive(Some(Duration::from_secs(30)))
-        .user_agent("smrt-mcp-backend/0.1 (+github.com/your-org)");
-
-    // proxy (biarkan seperti sebelumnya)
-    if let Ok(p) = std::env::var("HTTPS_PROXY").or_else(|_| std::env::var("https_proxy")) {
-        if let Ok(proxy) = reqwest::Proxy::https(&p) {
-            b = b.proxy(proxy);
+    pub(crate) fn sampling_overrides(&self) -> SamplingOverrides {
+        SamplingOverrides {
+            temperature: self.temperature,
+            top_p: self.top_p,
+            max_tokens: self.max_tokens,
+            stop: self.stop.clone(),
         }
     }
-    if let Ok(p) = std::env::var("HTTP_PROXY").or_else(|_| std::env::var("http_proxy")) {
-        if let Ok(proxy) = reqwest::Proxy::http(&p) {
-            b = b.proxy(proxy);
+
+    /// Override endpoint dari client menang atas plan dari planner
+    pub(crate) fn apply_endpoint_override(&self, plan: &mut RouterPlan) {
+        if let Some(eps) = self.endpoints.as_ref().filter(|e| !e.is_empty()) {
+            plan.endpoints = eps.clone();
         }
     }
+}
 
-    // (opsional) jika butuh: paksa HTTP/1.1 untuk menghindari h2 handshake isu
-    if std::env::var("FORCE_HTTP1").ok().as_deref() == Some("1") {
-        b = b.http1_only();
-    }
+/// Reconnect manual (client non-EventSource); EventSource memakai header Last-Event-ID
+#[derive(Deserialize, Debug)]
+pub struct ResumeQuery {
+    pub last_event_id: Option<String>,
+}
 
-    b.build().map_err(to_axum_error)
+/// Wajib sama dengan session_id yang membuka stream (kalau stream punya session)
+#[derive(Deserialize, Debug, Default)]
+pub struct CancelQuery {
+    pub session_id: Option<String>,
 }
 
-/// Client without proxy — for internal loopback calls
-fn build_client_no_proxy(timeout: Duration) -> Result<reqwest::Client, axum::Error> {
-    reqwest::Client::builder()
-        .timeout(timeout)
-        .no_proxy()
-        .build()
-        .map_err(to_axum_error)
+#[derive(Serialize)]
+pub struct ChatResponse {
+    pub reply: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structured: Option<StructuredAnswer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
 }
 
-fn internal_error<E: std::fmt::Display>(e: E) -> (axum::http::StatusCode, String) {
-    tracing::error!("internal error: {e}");
-    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+/* ------------------------- Helpers ------------------------- */
+
+pub(crate) fn request_id(headers: &HeaderMap) -> String {
+    headers
+        .get("x-request-id")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("no-request-id")
+        .to_string()
 }
 
-fn to_axum_error<E>(e: E) -> axum::Error
-where
-    E: std::error::Error + Send + Sync + 'static,
-{
-    let mut src: &(dyn std::error::Error + 'static) = &e;
-    tracing::error!("stream error: {}", src);
-    while let Some(cause) = src.source() {
-        tracing::error!("  caused by: {}", cause);
-        src = cause;
+pub(crate) fn internal_base_url(cfg: &Config, headers_opt: Option<&HeaderMap>) -> String {
+    // 1) self_base_url dari config kalau diisi
+    if let Some(v) = &cfg.self_base_url {
+        return v.clone();
     }
-    axum::Error::new(e)
+    // 2) Fallback to Host header
+    let host = headers_opt
+        .and_then(|h| h.get("host"))
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("127.0.0.1:8080");
+    format!("http://{host}")
 }
 
 /* ------------------------- Non-stream ------------------------- */
 
-#[tracing::instrument(skip(_pool, payload), fields(text = %payload.text))]
+/// POST /api/chat — pipeline yang sama dengan stream, token dikumpulkan jadi satu jawaban
+#[tracing::instrument(skip(pool, config, headers, payload), fields(text = %payload.text))]
 pub async fn chat_handler(
-    State(_pool): State<MySqlPool>,
+    State(pool): State<MySqlPool>,
+    State(config): State<SharedConfig>,
+    headers: HeaderMap,
     Json(payload): Json<ChatRequest>,
 ) -> Result<Json<ChatResponse>, (axum::http::StatusCode, String)> {
-    let cfg = Config::from_env();
-
-    // Upstream (OpenAI): may proxy
-    let client_up = build_client(Duration::from_secs(60)).map_err(internal_error)?;
-    // Internal fetch: no proxy
-    let client_in = build_client_no_proxy(Duration::from_secs(15)).map_err(internal_error)?;
-
-    // ===== 1) Planner =====
-    let plan_prompt = intent_prompt(&cfg.system_prompt, &payload.text);
-    let plan_req = serde_json::json!({
-        "model": &cfg.model,
-        "messages": [
-            { "role": "system", "content": "Return JSON only. No prose." },
-            { "role": "user",   "content": plan_prompt }
-        ],
-        "temperature": 0.0
-    });
-
-    let plan_txt = {
-        let r = client_up
-            .post("https://api.openai.com/v1/chat/completions")
-            .bearer_auth(&cfg.openai_api_key)
-            .json(&plan_req)
-            .send()
-            .await
-            .map_err(internal_error)?;
-        let status = r.status();
-        if !status.is_success() {
-            let body = r.text().await.unwrap_or_default();
-            tracing::warn!(%status, body = %body, "planner failed; fallback heuristic");
-            String::new()
-        } else {
-            #[derive(Deserialize)]
-            struct Choice {
-                message: MsgOut,
-            }
-            #[derive(Deserialize)]
-            struct MsgOut {
-                content: String,
-            }
-            #[derive(Deserialize)]
-            struct PlanResp {
-                choices: Vec<Choice>,
-            }
-            let pr: PlanResp = r.json().await.map_err(internal_error)?;
-            pr.choices
-                .get(0)
-                .map(|c| c.message.content.clone())
-                .unwrap_or_default()
-        }
+    payload
+        .validate()
+        .map_err(|e| (axum::http::StatusCode::BAD_REQUEST, e))?;
+
+    let req_id = request_id(&headers);
+    let tracer = TraceRecorder::new(pool.clone(), req_id.clone());
+    let cfg = Config::clone(&config.current());
+    let ctx = PipelineCtx {
+        pool,
+        base_url: internal_base_url(&cfg, Some(&headers)),
+        cfg,
+        req_id,
+        tracer: tracer.clone(),
     };
-
-    let mut plan = parse_or_fallback(&plan_txt, &payload.text);
-
-    // Inject params dari payload
-    if let Some(df) = &payload.date_from {
-        plan.params.insert("date_from".into(), df.clone());
-    }
-    if let Some(dt) = &payload.date_to {
-        plan.params.insert("date_to".into(), dt.clone());
-    }
-    if let Some(tz) = &payload.tz {
-        plan.params.insert("tz".into(), tz.clone());
-    }
-
-    // Fallback regex untuk service
-    if !plan.params.contains_key("service") {
-        let t = payload.text.to_lowercase();
-        let regex_guess = Regex::new(r"([a-z0-9\-]+)\s+service").ok().and_then(|re| {
-            re.captures(&t)
-                .and_then(|c| c.get(1).map(|m| m.as_str().to_string()))
-        });
-        let simple_guess = if t.contains("payments") || t.contains("payment") {
-            Some("payments".to_string())
-        } else if t.contains("auth") {
-            Some("auth-service".to_string())
-        } else if t.contains("orders") || t.contains("order") {
-            Some("orders".to_string())
-        } else {
-            None
-        };
-        if let Some(svc) = simple_guess.or(regex_guess) {
-            plan.params.insert("service".into(), svc);
+    let session_id = payload.session_id.clone();
+
+    match pipeline::collect_reply(pipeline::run(ctx, payload, None)).await {
+        Ok(out) => Ok(Json(ChatResponse {
+            reply: out.reply,
+            structured: out.structured,
+            session_id,
+        })),
+        Err(e) => {
+            tracer.record("failed", serde_json::json!({ "error": e.to_string() }));
+            Err(internal_error(e))
         }
     }
+}
 
-    tracing::info!(?plan, "🧭 router plan");
-
-    // ===== 2) Fetch & Join (no-proxy) =====
-    let base_url = internal_base_url(None);
-    let joined = fetch_join(&client_in, &base_url, &plan.endpoints, &plan.params)
-        .await
-        .map_err(|e| internal_error(format!("join error: {e}")))?;
+/* ------------------------- Stream (SSE) ------------------------- */
 
-    // ===== 3) Final answer =====
-    #[derive(Serialize)]
-    struct Msg<'a> {
-        role: &'a str,
-        content: &'a str,
-    }
-    #[derive(Serialize)]
-    struct Req<'a> {
-        model: &'a str,
-        messages: Vec<Msg<'a>>,
-        temperature: f32,
-    }
-    #[derive(Deserialize)]
-    struct Choice {
-        message: MsgOut,
-    }
-    #[derive(Deserialize)]
-    struct MsgOut {
-        role: String,
-        content: String,
-    }
-    #[derive(Deserialize)]
-    struct Resp {
-        choices: Vec<Choice>,
-    }
+/// Jeda reconnect yang disarankan ke EventSource (field `retry:`)
+const SSE_RETRY: Duration = Duration::from_secs(2);
+
+/// SSE dari buffer: replay event dengan seq > `after`, lalu ikuti yang baru
+fn sse_from_buffer(
+    buf: Arc<StreamBuffer>,
+    after: u64,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream_id = buf.id().to_string();
+    let mut first = true;
+    let events = buf.subscribe(after).map(move |ev| {
+        let mut out = Event::default()
+            .event(ev.event)
+            .id(format!("{stream_id}:{}", ev.seq))
+            .data(ev.data);
+        if std::mem::take(&mut first) {
+            out = out.retry(SSE_RETRY);
+        }
+        Ok(out)
+    });
 
-    let context = serde_json::to_string_pretty(&joined).unwrap_or_else(|_| "{}".into());
-    let user_full = format!("Question: {}\n\nJoined data:\n{}", payload.text, context);
+    Sse::new(events).keep_alive(
+        KeepAlive::new()
+            .interval(Duration::from_secs(10))
+            .text("💓"),
+    )
+}
 
-    let body = Req {
-        model: &cfg.model,
-        messages: vec![
-            Msg {
-                role: "system",
-                content: &cfg.system_prompt,
-            },
-            Msg {
-                role: "user",
-                content: &user_full,
+/// Jalankan pipeline di task sendiri dan tulis hasilnya ke buffer, supaya
+/// koneksi yang putus tidak menghentikan jawaban yang sedang dibuat.
+///
+/// Saat buffer dibatalkan (endpoint cancel / client tidak kembali), pipeline
+/// di-drop: fetch internal dan request upstream yang sedang jalan ikut batal.
+async fn drive_pipeline<S>(
+    pipeline: S,
+    buffer: Arc<StreamBuffer>,
+    tracer: TraceRecorder,
+    resume_window: Duration,
+) where
+    S: Stream<Item = Result<ChatEvent, axum::Error>>,
+{
+    futures_util::pin_mut!(pipeline);
+    loop {
+        tokio::select! {
+            biased;
+            _ = buffer.cancelled() => {
+                let reason = buffer.cancel_reason().unwrap_or_default();
+                tracing::info!(stream_id = %buffer.id(), %reason, "🛑 chat stream cancelled");
+                tracer.record("cancelled", serde_json::json!({ "reason": reason }));
+                buffer.push("cancelled", reason);
+                buffer.push("done", "done");
+                break;
+            }
+            item = pipeline.next() => match item {
+                Some(Ok(ev)) => {
+                    buffer.push(ev.name(), ev.data());
+                }
+                Some(Err(e)) => {
+                    tracer.record("failed", serde_json::json!({ "error": e.to_string() }));
+                    buffer.push("failed", e.to_string());
+                    buffer.push("done", "done");
+                    break;
+                }
+                None => break,
             },
-        ],
-        temperature: 0.2,
-    };
-
-    let resp = client_up
-        .post("https://api.openai.com/v1/chat/completions")
-        .bearer_auth(cfg.openai_api_key)
-        .json(&body)
-        .send()
-        .await
-        .map_err(internal_error)?;
-
-    if !resp.status().is_success() {
-        let status = resp.status();
-        let s = resp.text().await.unwrap_or_default();
-        #[derive(Deserialize)]
-        struct OaiErr {
-            error: OaiErrInner,
         }
-        #[derive(Deserialize)]
-        struct OaiErrInner {
-            message: String,
-            r#type: String,
-            code: Option<String>,
-        }
-        let msg = serde_json::from_str::<OaiErr>(&s)
-            .map(|e| {
-                format!(
-                    "OpenAI error: {} (type={}, code={:?})",
-                    e.error.message, e.error.r#type, e.error.code
-                )
-            })
-            .unwrap_or_else(|_| format!("OpenAI error {status}: {s}"));
-        return Err((axum::http::StatusCode::BAD_GATEWAY, msg));
     }
-
-    let parsed: Resp = resp.json().await.map_err(internal_error)?;
-    let content = parsed
-        .choices
-        .get(0)
-        .map(|c| c.message.content.clone())
-        .unwrap_or_else(|| "No content".to_string());
-
-    Ok(Json(ChatResponse { reply: content }))
+    stream_buffer::finish_and_expire(buffer, resume_window);
 }
 
-/* ------------------------- Stream (SSE) ------------------------- */
-
-#[tracing::instrument(skip(_pool, headers, q))]
+#[tracing::instrument(skip(pool, config, headers, q, resume))]
 pub async fn chat_stream_handler(
-    State(_pool): State<MySqlPool>,
+    State(pool): State<MySqlPool>,
+    State(config): State<SharedConfig>,
     headers: HeaderMap,
     Query(q): Query<ChatRequest>,
-) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
-    let req_id = headers
-        .get("x-request-id")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("no-request-id")
-        .to_string();
-
-    let cfg = Config::from_env();
-    let model = cfg.model.clone();
-    let system_prompt = cfg.system_prompt.clone();
-    let user_text = q.text.clone();
-
-    let stream = async_stream::try_stream! {
-            // received
-            yield Event::default().event("received").id(req_id.clone()).data(user_text.clone());
-
-            if cfg.openai_api_key.trim().is_empty() {
-                yield Event::default().event("token").id(req_id.clone()).data("(missing OPENAI_API_KEY)");
-                yield Event::default().event("done").id(req_id.clone()).data("done");
-                return;
-            }
-
-            // Upstream client (may proxy) & internal client (no proxy)
-            let client_up = build_client(Duration::from_secs(60))?;
-            let client_in = build_client_no_proxy(Duration::from_secs(15))?;
-
-            // llm_start(plan)
-            yield Event::default().event("llm_start").id(req_id.clone()).data("plan");
-
-            // Planner
-            let plan_prompt_str = intent_prompt(&system_prompt, &user_text);
-            let plan_body = serde_json::json!({
-                "model": model,
-                "messages": [
-                    { "role": "system", "content": "Return JSON only. No prose." },
-                    { "role": "user",   "content": plan_prompt_str }
-                ],
-                "temperature": 0.0
-            });
-
-            let plan_res = client_up
-                .post("https://api.openai.com/v1/chat/completions")
-                .bearer_auth(&cfg.openai_api_key)
-                .json(&plan_body)
-                .send()
-                .await;
-
-            let plan_text = match plan_res {
-                Ok(rsp) if rsp.status().is_success() => {
-                    #[derive(Deserialize)] struct Choice { message: MsgOut }
-                    #[derive(Deserialize)] struct MsgOut { content: String }
-                    #[derive(Deserialize)] struct PlanResp { choices: Vec<Choice> }
-                    let pr: PlanResp = rsp.json().await.map_err(to_axum_error)?;
-                    pr.choices.get(0).map(|c| c.message.content.clone()).unwrap_or_default()
-                },
-                Ok(rsp) => {
-                    let st = rsp.status();
-                    let body = rsp.text().await.unwrap_or_default();
-                    tracing::warn!(%st, body = %body, "planner non-200; using heuristic");
-                    String::new()
-                }
-                Err(e) => {
-                    tracing::warn!("planner request failed: {e}; using heuristic");
-                    String::new()
-                }
-            };
-
-            // build plan (fallback → heuristic)
-            let mut plan = parse_or_fallback(&plan_text, &user_text);
-            if let Some(df) = &q.date_from { plan.params.insert("date_from".into(), df.clone()); }
-            if let Some(dt) = &q.date_to   { plan.params.insert("date_to".into(),   dt.clone()); }
-            if let Some(tz) = &q.tz        { plan.params.insert("tz".into(),        tz.clone()); }
-
-            // Fallback regex service
-            if !plan.params.contains_key("service") {
-                let t = user_text.to_lowercase();
-                let regex_guess = Regex::new(r"([a-z0-9\-]+)\s+service").ok()
-                    .and_then(|re| re.captures(&t).and_then(|c| c.get(1).map(|m| m.as_str().to_string())));
-                let simple_guess = if t.contains("payments") || t.contains("payment") {
-                    Some("payments".to_string())
-                } else if t.contains("auth") {
-                    Some("auth-service".to_string())
-                } else if t.contains("orders") || t.contains("order") {
-                    Some("orders".to_string())
-                } else { None };
-                if let Some(svc) = simple_guess.or(regex_guess) {
-                    plan.params.insert("service".into(), svc);
-                }
-            }
-
-            // route_planned
-            let planned_json = serde_json::to_string(&plan).unwrap_or_else(|_| "{}".into());
-            yield Event::default().event("route_planned").id(req_id.clone()).data(planned_json.clone());
-
-            // fetch_progress (internal, no proxy)
-            let base_url = internal_base_url(Some(&headers));
-            let mut joined = Vec::<serde_json::Value>::with_capacity(plan.endpoints.len());
-
-            for ep in &plan.endpoints {
-                yield Event::default().event("fetch_progress").id(req_id.clone()).data(
-                    serde_json::json!({ "endpoint": ep, "status": "start" }).to_string()
-                );
-
-                let res = fetch_one(&client_in, &base_url, ep, &plan.params).await;
-
-                let (status, data) = match res {
-                    Ok(v) => ("ok", v),
-                    Err(e) => ("error", serde_json::json!({ "error": e.to_string() })),
-                };
+    Query(resume): Query<ResumeQuery>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    open_stream(pool, &config, &headers, q, resume.last_event_id)
+}
 
-                yield Event::default().event("fetch_progress").id(req_id.clone()).data(
-                    serde_json::json!({ "endpoint": ep, "status": status }).to_string()
-                );
+/// POST /api/chat/stream — sama dengan GET, tapi ChatRequest lewat body JSON
+/// (pertanyaan panjang & opsi terstruktur tidak masuk URI/log TraceLayer)
+#[tracing::instrument(skip(pool, config, headers, q))]
+pub async fn chat_stream_post_handler(
+    State(pool): State<MySqlPool>,
+    State(config): State<SharedConfig>,
+    headers: HeaderMap,
+    Json(q): Json<ChatRequest>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (axum::http::StatusCode, String)> {
+    q.validate()
+        .map_err(|e| (axum::http::StatusCode::BAD_REQUEST, e))?;
+    Ok(open_stream(pool, &config, &headers, q, None))
+}
 
-                joined.push(serde_json::json!({ "endpoint": ep, "data": data }));
+/// Resume stream lama (Last-Event-ID) atau mulai pipeline baru
+fn open_stream(
+    pool: MySqlPool,
+    config: &SharedConfig,
+    headers: &HeaderMap,
+    q: ChatRequest,
+    last_event_id: Option<String>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let cfg = Config::clone(&config.current());
+
+    // ===== Resume: id event = "{stream_id}:{seq}" =====
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+        .or(last_event_id);
+    let mut resume_failed = None;
+    if let Some(last) = last_event_id {
+        let found = stream_buffer::parse_event_id(&last)
+            .and_then(|(sid, seq)| stream_buffer::get(sid).map(|buf| (buf, seq)));
+        match found {
+            Some((buf, seq)) => {
+                tracing::info!(stream_id = %buf.id(), after = seq, "🔁 resuming chat stream");
+                return sse_from_buffer(buf, seq);
             }
-
-            let joined_json = serde_json::json!({ "results": joined });
-            let joined_pretty = serde_json::to_string_pretty(&joined_json).unwrap_or_else(|_| "{}".into());
-
-            // joined
-            yield Event::default().event("joined").id(req_id.clone()).data(joined_pretty.clone());
-
-            // short-circuit if all endpoints errored
-            let all_err = joined_json["results"].as_array().map(|arr| arr.iter().all(|it| it["data"].get("error").is_some())).unwrap_or(false);
-            if all_err {
-                let first_err = joined_json["results"][0]["data"]["error"].as_str().unwrap_or("unknown error");
-                let hint = "Hint: ensure params.service is set (e.g. payments) and backend can reach /api/runtime-logs.";
-                let msg = format!("(fetch error) {first_err}\n{hint}");
-                yield Event::default().event("token").id(req_id.clone()).data(msg);
-                yield Event::default().event("done").id(req_id.clone()).data("done");
-                return;
+            None => {
+                tracing::warn!(%last, "resume requested but stream expired; restarting pipeline");
+                resume_failed = Some(last);
             }
-
-            // llm_start(answer)
-            yield Event::default().event("llm_start").id(req_id.clone()).data("answer");
-
-            // Final stream call (upstream)
-            let final_prompt = serde_json::json!({
-                "model": model,
-                "stream": true,
-                "messages": [
-                    { "role": "system", "content": system_prompt },
-                    { "role": "user",   "content": format!("Question: {}\n\nJoined data:\n{}", user_text, joined_pretty) }
-                ],
-                "temperature": 0.2
-            });
-
-           let send_res = client_up
-        .post("https://api.openai.com/v1/chat/completions")
-        .bearer_auth(cfg.openai_api_key.clone())
-        .json(&final_prompt)
-        .send()
-        .await;
-
-        // === retry sekali kalau error kirim (transient) ===
-    let send_res = match send_res {
-        Ok(r) => Ok(r),
-        Err(e1) => {
-            tracing::warn!("openai send error (first try): {e1}; retrying once...");
-            tokio::time::sleep(Duration::from_millis(250)).await;
-            client_up
-                .post("https://api.openai.com/v1/chat/completions")
-                .bearer_auth(cfg.openai_api_key.clone())
-                .json(&final_prompt)
-                .send()
-                .await
         }
-    };
+    }
 
-            // === Fallback jika pengiriman upstream gagal ===
-            let resp = match send_res {
-                Ok(r) => r,
-                Err(e) => {
-                    // 🔁 Local fallback renderer (no LLM)
-                    let logs = joined_json["results"]
-                        .get(0).and_then(|x| x.get("data"))
-                        .and_then(|d| d.get("logs")).and_then(|l| l.as_array())
-                        .cloned().unwrap_or_default();
-
-                    let mut counts = std::collections::BTreeMap::<String, usize>::new();
-                    for item in &logs {
-                        let lvl = item.get("level").and_then(|v| v.as_str()).unwrap_or("UNKNOWN").to_string();
-                        *counts.entry(lvl).or_insert(0) += 1;
-                    }
-
-                    let service = joined_json["results"]
-                        .get(0).and_then(|x| x.get("data")).and_then(|d| d.get("service"))
-                        .and_then(|s| s.as_str()).unwrap_or("unknown");
-                    let tz = joined_json["results"]
-                        .get(0).and_then(|x| x.get("data")).and_then(|d| d.get("tz"))
-                        .and_then(|s| s.as_str()).unwrap_or("UTC");
-                    let checked_at = joined_json["results"]
-                        .get(0).and_then(|x| x.get("data")).and_then(|d| d.get("checked_at"))
-                        .and_then(|s| s.as_str()).unwrap_or("-");
-
-                    let header = format!("Runtime logs (service={service}, tz={tz}) — checked_at={checked_at}\n");
-                    yield Event::default().event("token").id(req_id.clone()).data(header);
-
-                    for (lvl, c) in counts {
-                        let line = format!("• {lvl}: {c}\n");
-                        yield Event::default().event("token").id(req_id.clone()).data(line);
-                    }
-
-                    let tail_n = 10usize;
-                    let start = logs.len().saturating_sub(tail_n);
-                    if !logs.is_empty() {
-                        yield Event::default().event("token").id(req_id.clone()).data("\nLast lines:\n");
-                    }
-                    for item in logs.iter().skip(start) {
-                        let ts = item.get("ts").and_then(|v| v.as_str()).unwrap_or("-");
-                        let lvl = item.get("level").and_then(|v| v.as_str()).unwrap_or("-");
-                        let msg = item.get("message").and_then(|v| v.as_str()).unwrap_or("-");
-                        let line = format!("[{ts}] {lvl}: {msg}\n");
-                        yield Event::default().event("token").id(req_id.clone()).data(line);
-                    }
-
-                    let footer = format!("\n(note) LLM formatting skipped: {e}\n");
-                    yield Event::default().event("token").id(req_id.clone()).data(footer);
-                    yield Event::default().event("done").id(req_id.clone()).data("done");
-                    return;
-                }
-            };
-
-            // === Fallback jika upstream balas non-200 ===
-            if !resp.status().is_success() {
-                let status = resp.status();
-                let s = resp.text().await.unwrap_or_default();
-                #[derive(Deserialize)] struct OaiErr { error: OaiErrInner }
-                #[derive(Deserialize)] struct OaiErrInner { message: String, r#type: String, code: Option<String> }
-                let msg = match serde_json::from_str::<OaiErr>(&s) {
-                    Ok(e) => format!("OpenAI error: {} (type={}, code={:?})", e.error.message, e.error.r#type, e.error.code),
-                    Err(_) => format!("OpenAI error {status}: {s}"),
-                };
-
-                let logs = joined_json["results"]
-                    .get(0).and_then(|x| x.get("data"))
-                    .and_then(|d| d.get("logs")).and_then(|l| l.as_array())
-                    .cloned().unwrap_or_default();
-
-                let mut counts = std::collections::BTreeMap::<String, usize>::new();
-                for item in &logs {
-                    let lvl = item.get("level").and_then(|v| v.as_str()).unwrap_or("UNKNOWN").to_string();
-                    *counts.entry(lvl).or_insert(0) += 1;
-                }
+    // id stream dibuat server; x-request-id hanya untuk trace
+    let buffer = stream_buffer::register_new(
+        q.session_id.as_deref(),
+        Duration::from_secs(cfg.sse_orphan_grace_secs),
+    );
+    if let Some(last) = resume_failed {
+        // client harus membuang jawaban parsial; pipeline diulang dari awal
+        buffer.push("resume_failed", last);
+    }
 
-                yield Event::default().event("token").id(req_id.clone()).data("LLM formatting unavailable; showing raw summary:\n");
-                for (lvl, c) in counts {
-                    yield Event::default().event("token").id(req_id.clone()).data(format!("• {lvl}: {c}\n"));
-                }
-                let start = logs.len().saturating_sub(10);
-                if !logs.is_empty() {
-                    yield Event::default().event("token").id(req_id.clone()).data("\nLast lines:\n");
-                }
-                for item in logs.iter().skip(start) {
-                    let ts = item.get("ts").and_then(|v| v.as_str()).unwrap_or("-");
-                    let lvl = item.get("level").and_then(|v| v.as_str()).unwrap_or("-");
-                    let msg_line = item.get("message").and_then(|v| v.as_str()).unwrap_or("-");
-                    yield Event::default().event("token").id(req_id.clone()).data(format!("[{ts}] {lvl}: {msg_line}\n"));
-                }
-                yield Event::default().event("token").id(req_id.clone()).data(format!("\n(note) {msg}\n"));
-                yield Event::default().event("done").id(req_id.clone()).data("done");
-                return;
-            }
+    let base_url = internal_base_url(&cfg, Some(headers));
+    spawn_pipeline(
+        buffer.clone(),
+        request_id(headers),
+        pool,
+        cfg,
+        base_url,
+        q,
+        None,
+    );
+    sse_from_buffer(buffer, 0)
+}
 
-            // stream tokens
-            let mut lines = resp.bytes_stream();
-            let mut buf = Vec::<u8>::new();
-
-            while let Some(chunk) = lines.try_next().await.map_err(to_axum_error)? {
-                buf.extend_from_slice(&chunk);
-
-                loop {
-                    if let Some(pos) = buf.iter().position(|&b| b == b'\n') {
-                        let line_bytes = buf.drain(..=pos).collect::<Vec<u8>>();
-                        let line = String::from_utf8_lossy(&line_bytes).trim().to_string();
-                        if line.is_empty() { continue; }
-                        if !line.starts_with("data:") { continue; }
-
-                        let data = line.trim_start_matches("data:").trim().to_string();
-                        if data == "[DONE]" { break; }
-
-                        #[derive(Deserialize, Debug)] struct StreamDelta { content: Option<String> }
-                        #[derive(Deserialize, Debug)] struct StreamChoice { delta: StreamDelta, finish_reason: Option<String> }
-                        #[derive(Deserialize, Debug)] struct StreamResp { choices: Vec<StreamChoice> }
-
-                        if let Ok(sr) = serde_json::from_str::<StreamResp>(&data) {
-                            if let Some(choice) = sr.choices.get(0) {
-                                if let Some(token) = &choice.delta.content {
-                                    yield Event::default().event("token").id(req_id.clone()).data(token.clone());
-                                }
-                            }
-                        }
-                    } else {
-                        break;
-                    }
-                }
-            }
+/// Mulai pipeline streaming di background; event ditulis ke `buffer`,
+/// trace dicatat dengan `req_id`. Dipakai SSE dan WebSocket.
+pub(crate) fn spawn_pipeline(
+    buffer: Arc<StreamBuffer>,
+    req_id: String,
+    pool: MySqlPool,
+    cfg: Config,
+    base_url: String,
+    q: ChatRequest,
+    clarifications: Option<PendingClarification>,
+) {
+    tracing::info!(stream_id = %buffer.id(), %req_id, "▶️ chat stream started");
+    let resume_window = Duration::from_secs(cfg.sse_resume_window_secs);
+    let tracer = TraceRecorder::new(pool.clone(), req_id.clone());
+    let ctx = PipelineCtx {
+        pool,
+        cfg,
+        base_url,
+        req_id,
+        tracer: tracer.clone(),
+    };
+    let pipeline = pipeline::run(ctx, q, clarifications);
+    tokio::spawn(
+        drive_pipeline(pipeline, buffer, tracer, resume_window)
+            .instrument(tracing::Span::current()),
+    );
+}
 
-            yield Event::default().event("done").id(req_id.clone()).data("done");
-        };
+/* ------------------------- Cancel ------------------------- */
+
+/// POST /api/chat/:stream_id/cancel?session_id=… — hentikan stream yang sedang berjalan.
+/// `stream_id` = bagian depan id event SSE (dibuat server, bukan x-request-id).
+pub async fn cancel_handler(
+    Path(stream_id): Path<String>,
+    Query(q): Query<CancelQuery>,
+) -> Result<(axum::http::StatusCode, Json<serde_json::Value>), (axum::http::StatusCode, String)> {
+    // session lain mendapat 404 yang sama, supaya keberadaan stream tidak bocor
+    let buf = stream_buffer::get(&stream_id)
+        .filter(|b| b.owned_by(q.session_id.as_deref()))
+        .ok_or((
+            axum::http::StatusCode::NOT_FOUND,
+            format!("stream {stream_id} not found (unknown or expired)"),
+        ))?;
+
+    if buf.cancel("client_request") {
+        tracing::info!(%stream_id, "🛑 cancel requested by client");
+        let body = serde_json::json!({ "stream_id": stream_id, "status": "cancelling" });
+        return Ok((axum::http::StatusCode::ACCEPTED, Json(body)));
+    }
 
-    Sse::new(stream).keep_alive(
-        KeepAlive::new()
-            .interval(Duration::from_secs(10))
-            .text("💓"),
-    )
+    let status = if buf.cancel_reason().is_some() {
+        "cancelled"
+    } else {
+        "finished"
+    };
+    let body = serde_json::json!({ "stream_id": stream_id, "status": status });
+    Ok((axum::http::StatusCode::CONFLICT, Json(body)))
 }
 
 /* ------------------------- Ping OpenAI (buat router.rs) ------------------------- */
 
-#[tracing::instrument]
-pub async fn openai_ping_handler() -> Result<String, (axum::http::StatusCode, String)> {
-    let cfg = Config::from_env();
-    let client = build_client(Duration::from_secs(20)).map_err(internal_error)?;
+#[tracing::instrument(skip(config))]
+pub async fn openai_ping_handler(
+    State(config): State<SharedConfig>,
+) -> Result<String, (axum::http::StatusCode, String)> {
+    let cfg = config.current();
+    let client = build_client(&cfg, Duration::from_secs(20)).map_err(internal_error)?;
     let resp = client
         .get("https://api.openai.com/v1/models")
-        .bearer_auth(cfg.openai_api_key)
+        .bearer_auth(&cfg.openai_api_key)
         .send()
         .await
         .map_err(internal_error)?;
@@ -625,3 +401,59 @@ pub async fn openai_ping_handler() -> Result<String, (axum::http::StatusCode, St
     let preview = &body[..body.len().min(400)];
     Ok(format!("status={status}; body={preview}"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn req(json: &str) -> ChatRequest {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_session_and_unknown_endpoint() {
+        assert!(req(r#"{"text":"hi","session_id":"abc-1"}"#)
+            .validate()
+            .is_ok());
+        assert!(req(r#"{"text":"hi","session_id":"a b"}"#)
+            .validate()
+            .is_err());
+        let err = req(r#"{"text":"hi","endpoints":["/api/db-perf","/etc/passwd"]}"#)
+            .validate()
+            .unwrap_err();
+        assert!(err.contains("/etc/passwd"));
+    }
+
+    #[test]
+    fn test_endpoint_override_replaces_planner_choice() {
+        let mut plan = RouterPlan::new("logs_fetch", vec!["/api/runtime-logs"]);
+        req(r#"{"text":"hi","endpoints":[]}"#).apply_endpoint_override(&mut plan);
+        assert_eq!(plan.endpoints, vec!["/api/runtime-logs"]);
+
+        req(r#"{"text":"hi","endpoints":["/api/db-perf"]}"#).apply_endpoint_override(&mut plan);
+        assert_eq!(plan.endpoints, vec!["/api/db-perf"]);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_requires_owning_session() {
+        let buf = stream_buffer::register_new(Some("sess-a"), Duration::from_secs(60));
+        let cancel = |sid: Option<&str>| {
+            cancel_handler(
+                Path(buf.id().to_string()),
+                Query(CancelQuery {
+                    session_id: sid.map(str::to_string),
+                }),
+            )
+        };
+
+        for other in [None, Some("sess-b")] {
+            let (status, _) = cancel(other).await.unwrap_err();
+            assert_eq!(status, axum::http::StatusCode::NOT_FOUND);
+        }
+        assert!(buf.cancel_reason().is_none());
+
+        let (status, _) = cancel(Some("sess-a")).await.unwrap();
+        assert_eq!(status, axum::http::StatusCode::ACCEPTED);
+        assert_eq!(buf.cancel_reason().as_deref(), Some("client_request"));
+    }
+}
@@ -0,0 +1,113 @@
+// backend/src/handlers/chat_logs.rs
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlPool, QueryBuilder};
+
+use super::internal_error;
+use crate::models::{ApiResult, ChatLog};
+
+#[derive(Debug, Deserialize)]
+pub struct ChatListQuery {
+    pub user_id: Option<String>,
+    pub session_id: Option<String>,
+    pub intent: Option<String>,
+    pub date_from: Option<String>, // YYYY-MM-DD (inklusif)
+    pub date_to: Option<String>,   // YYYY-MM-DD (inklusif)
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChatListResp {
+    pub items: Vec<ChatLog>,
+    pub limit: u32,
+    pub offset: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChatDetailResp {
+    #[serde(flatten)]
+    pub chat: ChatLog,
+    pub api_results: Vec<ApiResult>,
+}
+
+pub(crate) const CHAT_LOG_COLUMNS: &str =
+    "id, request_id, session_id, user_id, user_query, detected_intent, \
+     routed_endpoints, response_summary, created_at";
+
+/// GET /api/chats?user_id=admin&intent=logs_fetch&date_from=2025-09-01&date_to=2025-09-30&limit=50
+pub async fn list_chats(
+    State(pool): State<MySqlPool>,
+    Query(q): Query<ChatListQuery>,
+) -> Result<Json<ChatListResp>, (StatusCode, String)> {
+    let limit = q.limit.unwrap_or(50).clamp(1, 500);
+    let offset = q.offset.unwrap_or(0);
+
+    let mut qb = QueryBuilder::new(format!(
+        "SELECT {CHAT_LOG_COLUMNS} FROM chat_logs WHERE 1=1"
+    ));
+    if let Some(u) = &q.user_id {
+        qb.push(" AND user_id = ").push_bind(u.clone());
+    }
+    if let Some(s) = &q.session_id {
+        qb.push(" AND session_id = ").push_bind(s.clone());
+    }
+    if let Some(i) = &q.intent {
+        qb.push(" AND detected_intent = ").push_bind(i.clone());
+    }
+    if let Some(df) = &q.date_from {
+        qb.push(" AND created_at >= ").push_bind(df.clone());
+    }
+    if let Some(dt) = &q.date_to {
+        qb.push(" AND created_at < DATE_ADD(")
+            .push_bind(dt.clone())
+            .push(", INTERVAL 1 DAY)");
+    }
+    qb.push(" ORDER BY id DESC LIMIT ")
+        .push_bind(limit)
+        .push(" OFFSET ")
+        .push_bind(offset);
+
+    let items: Vec<ChatLog> = qb
+        .build_query_as()
+        .fetch_all(&pool)
+        .await
+        .map_err(internal_error)?;
+
+    Ok(Json(ChatListResp {
+        items,
+        limit,
+        offset,
+    }))
+}
+
+/// GET /api/chats/:id — chat log + semua api_results (params & response per endpoint)
+pub async fn get_chat(
+    State(pool): State<MySqlPool>,
+    Path(id): Path<i64>,
+) -> Result<Json<ChatDetailResp>, (StatusCode, String)> {
+    let chat: Option<ChatLog> = sqlx::query_as(&format!(
+        "SELECT {CHAT_LOG_COLUMNS} FROM chat_logs WHERE id = ?"
+    ))
+    .bind(id)
+    .fetch_optional(&pool)
+    .await
+    .map_err(internal_error)?;
+    let chat = chat.ok_or((StatusCode::NOT_FOUND, format!("chat {id} not found")))?;
+
+    let api_results: Vec<ApiResult> = sqlx::query_as(
+        "SELECT id, chat_log_id, endpoint, status, request_params, response_data, created_at
+         FROM api_results WHERE chat_log_id = ? ORDER BY id",
+    )
+    .bind(id)
+    .fetch_all(&pool)
+    .await
+    .map_err(internal_error)?;
+
+    Ok(Json(ChatDetailResp { chat, api_results }))
+}
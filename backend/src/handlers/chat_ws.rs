@@ -0,0 +1,237 @@
+// backend/src/handlers/chat_ws.rs
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    http::HeaderMap,
+    response::Response,
+};
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use sqlx::MySqlPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use super::chat::{internal_base_url, request_id, spawn_pipeline, ChatRequest};
+use crate::config::{Config, SharedConfig};
+use crate::pipeline::PendingClarification;
+use crate::stream_buffer::{self, StreamBuffer};
+
+/// Antrian frame keluar per koneksi; penuh = client lambat, forwarder menunggu
+const OUTBOUND_CAPACITY: usize = 64;
+
+/// Pesan dari client. Contoh:
+/// `{"type":"ask","text":"errors payments kemarin","session_id":"..."}`,
+/// `{"type":"follow_up","text":"bagaimana dengan auth?"}`,
+/// `{"type":"clarification","answer":"payments"}`, `{"type":"cancel"}`
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WsClientMsg {
+    Ask(Box<ChatRequest>),
+    FollowUp { text: String },
+    Clarification { answer: String },
+    Cancel,
+}
+
+/// Satu pertanyaan yang sedang diproses di koneksi ini
+struct Run {
+    buffer: Arc<StreamBuffer>,
+    clarification: PendingClarification,
+}
+
+impl Run {
+    fn active(&self) -> bool {
+        !self.buffer.is_finished()
+    }
+}
+
+/// Frame server → client; event & data sama dengan SSE, id = "{run_id}:{seq}"
+fn frame(id: Option<String>, event: &str, data: &str) -> Message {
+    Message::Text(serde_json::json!({ "id": id, "event": event, "data": data }).to_string())
+}
+
+/// GET /api/chat/ws — chat dua arah: event pipeline sama dengan SSE, plus
+/// client bisa cancel, kirim follow-up, dan menjawab pertanyaan klarifikasi
+pub async fn chat_ws_handler(
+    ws: WebSocketUpgrade,
+    State(pool): State<MySqlPool>,
+    State(config): State<SharedConfig>,
+    headers: HeaderMap,
+) -> Response {
+    let conn_id = request_id(&headers);
+    let base_url = internal_base_url(&config.current(), Some(&headers));
+    ws.on_upgrade(move |socket| handle_socket(socket, pool, config, base_url, conn_id))
+}
+
+#[tracing::instrument(skip(socket, pool, config, base_url))]
+async fn handle_socket(
+    socket: WebSocket,
+    pool: MySqlPool,
+    config: SharedConfig,
+    base_url: String,
+    conn_id: String,
+) {
+    let (mut sink, mut incoming) = socket.split();
+
+    // satu writer untuk socket; forwarder tiap run menulis lewat channel
+    let (out_tx, mut out_rx) = mpsc::channel::<Message>(OUTBOUND_CAPACITY);
+    let writer = tokio::spawn(async move {
+        while let Some(msg) = out_rx.recv().await {
+            if sink.send(msg).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let _ = out_tx.send(frame(None, "ready", &conn_id)).await;
+
+    let mut run: Option<Run> = None;
+    let mut last_ask: Option<ChatRequest> = None;
+    let mut n_runs = 0u32;
+
+    while let Some(Ok(msg)) = incoming.next().await {
+        let text = match msg {
+            Message::Text(t) => t,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        let parsed = match serde_json::from_str::<WsClientMsg>(&text) {
+            Ok(m) => m,
+            Err(e) => {
+                let _ = out_tx
+                    .send(frame(None, "error", &format!("invalid message: {e}")))
+                    .await;
+                continue;
+            }
+        };
+
+        let ask = match parsed {
+            WsClientMsg::Ask(mut req) => {
+                // tanpa session_id, koneksi ini sendiri menjadi session
+                req.session_id.get_or_insert_with(|| conn_id.clone());
+                *req
+            }
+            WsClientMsg::FollowUp { text } => match &last_ask {
+                Some(prev) => ChatRequest {
+                    text,
+                    ..prev.clone()
+                },
+                None => {
+                    let _ = out_tx
+                        .send(frame(None, "error", "follow_up requires a previous ask"))
+                        .await;
+                    continue;
+                }
+            },
+            WsClientMsg::Clarification { answer } => {
+                match run.as_ref().filter(|r| r.active()) {
+                    Some(r) if r.clarification.answer(answer) => {}
+                    _ => {
+                        let _ = out_tx
+                            .send(frame(None, "error", "no pending clarification"))
+                            .await;
+                    }
+                }
+                continue;
+            }
+            WsClientMsg::Cancel => {
+                match run.as_ref() {
+                    Some(r) if r.buffer.cancel("client_request") => {
+                        tracing::info!(stream_id = %r.buffer.id(), "🛑 cancel requested over websocket");
+                    }
+                    _ => {
+                        let _ = out_tx.send(frame(None, "error", "no active run")).await;
+                    }
+                }
+                continue;
+            }
+        };
+
+        if run.as_ref().is_some_and(Run::active) {
+            let _ = out_tx
+                .send(frame(
+                    None,
+                    "error",
+                    "a run is already in progress; cancel it first",
+                ))
+                .await;
+            continue;
+        }
+
+        n_runs += 1;
+        let cfg = Config::clone(&config.current());
+        let buffer = stream_buffer::register_new(
+            ask.session_id.as_deref(),
+            Duration::from_secs(cfg.sse_orphan_grace_secs),
+        );
+        let run_id = buffer.id().to_string();
+        let clarification = PendingClarification::default();
+        spawn_pipeline(
+            buffer.clone(),
+            format!("{conn_id}-{n_runs}"),
+            pool.clone(),
+            cfg,
+            base_url.clone(),
+            ask.clone(),
+            Some(clarification.clone()),
+        );
+
+        // forwarder: buffer → socket (berhenti sendiri saat run selesai)
+        let events = buffer.clone().subscribe(0);
+        let tx = out_tx.clone();
+        tokio::spawn(async move {
+            futures_util::pin_mut!(events);
+            while let Some(ev) = events.next().await {
+                let id = format!("{run_id}:{}", ev.seq);
+                if tx.send(frame(Some(id), &ev.event, &ev.data)).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        last_ask = Some(ask);
+        run = Some(Run {
+            buffer,
+            clarification,
+        });
+    }
+
+    if let Some(r) = run.filter(Run::active) {
+        r.buffer.cancel("client_disconnected");
+    }
+    drop(out_tx);
+    let _ = writer.await;
+    tracing::info!("websocket chat closed");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_client_messages() {
+        let m: WsClientMsg =
+            serde_json::from_str(r#"{"type":"ask","text":"errors payments","tz":"Asia/Jakarta"}"#)
+                .unwrap();
+        assert!(
+            matches!(m, WsClientMsg::Ask(ref r) if r.text == "errors payments" && r.tz.as_deref() == Some("Asia/Jakarta"))
+        );
+
+        let m: WsClientMsg =
+            serde_json::from_str(r#"{"type":"follow_up","text":"and auth?"}"#).unwrap();
+        assert!(matches!(m, WsClientMsg::FollowUp { ref text } if text == "and auth?"));
+
+        let m: WsClientMsg =
+            serde_json::from_str(r#"{"type":"clarification","answer":"orders"}"#).unwrap();
+        assert!(matches!(m, WsClientMsg::Clarification { ref answer } if answer == "orders"));
+
+        assert!(matches!(
+            serde_json::from_str(r#"{"type":"cancel"}"#).unwrap(),
+            WsClientMsg::Cancel
+        ));
+        assert!(serde_json::from_str::<WsClientMsg>(r#"{"type":"nope"}"#).is_err());
+    }
+}
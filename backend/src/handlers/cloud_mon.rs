@@ -1,21 +1,19 @@
 // backend/src/handlers/cloud_mon.rs
 
-use crate::router::Range;
-use crate::util::now_gmt8;
-use axum::{extract::Query, Json};
-use serde::Serialize;
+use axum::{
+    extract::{Query, State},
+    Json,
+};
 
-#[derive(Serialize)]
-pub struct CloudMonDummy {
-    adapter: &'static str,
-    resource: &'static str,
-    checked_at: String,
-}
+use crate::adapters::{cloud_mon::CloudMon, AdapterError, Params, Registry};
+use crate::router::Range;
 
-pub async fn get_cloud_mon(Query(_q): Query<Range>) -> Json<CloudMonDummy> {
-    Json(CloudMonDummy {
-        adapter: "cloud_monitoring",
-        resource: "payment-service@ecs",
-        checked_at: now_gmt8().to_rfc3339(),
-    })
+/// GET /api/cloud-mon
+pub async fn get_cloud_mon(
+    State(adapters): State<Registry>,
+    Query(q): Query<Range>,
+    Query(params): Query<Params>,
+) -> Result<Json<CloudMon>, AdapterError> {
+    let adapters = adapters.current();
+    adapters.cloud_mon.fetch(&q, &params).await.map(Json)
 }
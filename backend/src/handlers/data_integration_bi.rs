@@ -1,21 +1,23 @@
 // backend/src/handlers/data_integration_bi.rs
 
-use crate::router::Range;
-use crate::util::now_gmt8;
-use axum::{extract::Query, Json};
-use serde::Serialize;
+use axum::{
+    extract::{Query, State},
+    Json,
+};
 
-#[derive(Serialize)]
-pub struct DataIntegrationDummy {
-    adapter: &'static str,
-    pipeline: &'static str,
-    checked_at: String,
-}
+use crate::adapters::{data_integration_bi::DataIntegration, AdapterError, Params, Registry};
+use crate::router::Range;
 
-pub async fn get_bi(Query(_q): Query<Range>) -> Json<DataIntegrationDummy> {
-    Json(DataIntegrationDummy {
-        adapter: "data_integration_bi",
-        pipeline: "etl_ridership_to_bi",
-        checked_at: now_gmt8().to_rfc3339(),
-    })
+/// GET /api/data-integration-bi
+pub async fn get_bi(
+    State(adapters): State<Registry>,
+    Query(q): Query<Range>,
+    Query(params): Query<Params>,
+) -> Result<Json<DataIntegration>, AdapterError> {
+    let adapters = adapters.current();
+    adapters
+        .data_integration_bi
+        .fetch(&q, &params)
+        .await
+        .map(Json)
 }
@@ -1,20 +1,19 @@
 // backend/src/handlers/db_perf.rs
-use crate::router::Range;
-use crate::util::now_gmt8;
-use axum::{extract::Query, Json};
-use serde::Serialize;
 
-#[derive(Serialize)]
-pub struct DbPerfDummy {
-    adapter: &'static str,
-    query: &'static str,
-    checked_at: String,
-}
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+
+use crate::adapters::{db_perf::DbPerf, AdapterError, Params, Registry};
+use crate::router::Range;
 
-pub async fn get_db_perf(Query(_q): Query<Range>) -> Json<DbPerfDummy> {
-    Json(DbPerfDummy {
-        adapter: "db_perf",
-        query: "SELECT * FROM passenger_info WHERE ...",
-        checked_at: now_gmt8().to_rfc3339(),
-    })
+/// GET /api/db-perf
+pub async fn get_db_perf(
+    State(adapters): State<Registry>,
+    Query(q): Query<Range>,
+    Query(params): Query<Params>,
+) -> Result<Json<DbPerf>, AdapterError> {
+    let adapters = adapters.current();
+    adapters.db_perf.fetch(&q, &params).await.map(Json)
 }
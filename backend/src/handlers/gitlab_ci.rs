@@ -1,27 +1,19 @@
 // backend/src/handlers/gitlab_ci.rs
-// backend/src/handlers/gitlab_ci.rs
-use crate::router::Range;
-use crate::util::now_gmt8;
-use axum::{extract::Query, Json};
-use serde::Serialize;
 
-#[derive(Serialize)]
-pub struct GitlabCIDummy {
-    adapter: &'static str,
-    project: &'static str,
-    branch: &'static str,
-    status: &'static str,         // success | failed
-    checked_at: String,
-    failed_tests: Vec<&'static str>,
-}
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+
+use crate::adapters::{gitlab_ci::GitlabCi, AdapterError, Params, Registry};
+use crate::router::Range;
 
-pub async fn get_gitlab_ci(Query(_q): Query<Range>) -> Json<GitlabCIDummy> {
-    Json(GitlabCIDummy {
-        adapter: "gitlab_ci",
-        project: "ticketing-backend",
-        branch: "main",
-        status: "success", // dummy: CI terakhir sukses
-        checked_at: now_gmt8().to_rfc3339(),
-        failed_tests: vec![], // kosong kalau sukses
-    })
+/// GET /api/gitlab-ci?date_from=..&date_to=..
+pub async fn get_gitlab_ci(
+    State(adapters): State<Registry>,
+    Query(q): Query<Range>,
+    Query(params): Query<Params>,
+) -> Result<Json<GitlabCi>, AdapterError> {
+    let adapters = adapters.current();
+    adapters.gitlab_ci.fetch(&q, &params).await.map(Json)
 }
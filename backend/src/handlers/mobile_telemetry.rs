@@ -1,21 +1,19 @@
 // backend/src/handlers/mobile_telemetry.rs
 
-use crate::router::Range;
-use crate::util::now_gmt8;
-use axum::{extract::Query, Json};
-use serde::Serialize;
+use axum::{
+    extract::{Query, State},
+    Json,
+};
 
-#[derive(Serialize)]
-pub struct MobileTelemetryDummy {
-    adapter: &'static str,
-    platform: &'static str,
-    checked_at: String,
-}
+use crate::adapters::{mobile_telemetry::MobileTelemetry, AdapterError, Params, Registry};
+use crate::router::Range;
 
-pub async fn get_mobile(Query(_q): Query<Range>) -> Json<MobileTelemetryDummy> {
-    Json(MobileTelemetryDummy {
-        adapter: "mobile_telemetry",
-        platform: "android",
-        checked_at: now_gmt8().to_rfc3339(),
-    })
+/// GET /api/mobile-telemetry
+pub async fn get_mobile(
+    State(adapters): State<Registry>,
+    Query(q): Query<Range>,
+    Query(params): Query<Params>,
+) -> Result<Json<MobileTelemetry>, AdapterError> {
+    let adapters = adapters.current();
+    adapters.mobile_telemetry.fetch(&q, &params).await.map(Json)
 }
@@ -1,5 +1,8 @@
 // backend/src/handlers/mod.rs
+pub mod adapters;
 pub mod chat; // <— supaya handlers::chat terlihat
+pub mod chat_logs;
+pub mod chat_ws;
 pub mod cloud_mon;
 pub mod data_integration_bi;
 pub mod db_perf;
@@ -7,9 +10,18 @@ pub mod gitlab_ci;
 pub mod incident_metrics;
 pub mod mobile_telemetry;
 pub mod observability;
+pub mod profiles;
 pub mod runtime_logs;
 pub mod security_auth;
+pub mod sessions;
 pub mod settings;
+pub mod traces;
+pub mod usage;
 pub mod user_feedback; // <-- tambahkan ini
                        // pub mod gitlab_ci; ...
                        // dst.
+
+pub(crate) fn internal_error<E: std::fmt::Display>(e: E) -> (axum::http::StatusCode, String) {
+    tracing::error!("internal error: {e}");
+    (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+}
@@ -0,0 +1,241 @@
+// backend/src/handlers/profiles.rs
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use sqlx::{MySqlPool, QueryBuilder};
+
+use super::internal_error;
+use crate::mcp;
+use crate::models::SettingsProfile;
+use crate::profiles::{self, PROFILE_SELECT, PROVIDERS};
+use crate::sessions;
+
+#[derive(Debug, Deserialize)]
+pub struct ProfileListQuery {
+    pub team: Option<String>,
+}
+
+/// Body POST/PUT; field kosong = ikut settings global
+#[derive(Debug, Deserialize)]
+pub struct ProfileReq {
+    pub name: String,
+    pub team: Option<String>,
+    #[serde(default = "default_provider")]
+    pub provider: String,
+    pub model: Option<String>,
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    pub system_prompt: Option<String>,
+    pub response_prompt: Option<String>,
+    pub allowed_endpoints: Option<Vec<String>>,
+}
+
+fn default_provider() -> String {
+    "openai".into()
+}
+
+impl ProfileReq {
+    fn validate(&self) -> Result<(), String> {
+        if !sessions::valid_id(&self.name) {
+            return Err("name must be 1-64 chars of [A-Za-z0-9_-]".into());
+        }
+        if !PROVIDERS.contains(&self.provider.as_str()) {
+            return Err(format!(
+                "unsupported provider {} (supported: {})",
+                self.provider,
+                PROVIDERS.join(", ")
+            ));
+        }
+        if self.temperature.is_some_and(|t| !(0.0..=2.0).contains(&t)) {
+            return Err("temperature must be within 0.0..=2.0".into());
+        }
+        if self.top_p.is_some_and(|t| !(0.0..=1.0).contains(&t)) {
+            return Err("top_p must be within 0.0..=1.0".into());
+        }
+        if let Some(bad) = self
+            .allowed_endpoints
+            .iter()
+            .flatten()
+            .find(|e| !mcp::is_known_endpoint(e))
+        {
+            return Err(format!("unknown endpoint {bad}"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserProfileReq {
+    pub profile: Option<String>,
+}
+
+/// GET /api/profiles?team=db
+pub async fn list_profiles(
+    State(pool): State<MySqlPool>,
+    Query(q): Query<ProfileListQuery>,
+) -> Result<Json<Vec<SettingsProfile>>, (StatusCode, String)> {
+    let mut qb = QueryBuilder::new(PROFILE_SELECT);
+    if let Some(team) = &q.team {
+        qb.push(" WHERE team = ").push_bind(team);
+    }
+    qb.push(" ORDER BY name");
+    let rows = qb
+        .build_query_as::<SettingsProfile>()
+        .fetch_all(&pool)
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(rows))
+}
+
+/// GET /api/profiles/:name
+pub async fn get_profile(
+    State(pool): State<MySqlPool>,
+    Path(name): Path<String>,
+) -> Result<Json<SettingsProfile>, (StatusCode, String)> {
+    profiles::get(&pool, &name)
+        .await
+        .map_err(internal_error)?
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, format!("profile {name} not found")))
+}
+
+/// POST /api/profiles
+pub async fn create_profile(
+    State(pool): State<MySqlPool>,
+    Json(req): Json<ProfileReq>,
+) -> Result<(StatusCode, Json<SettingsProfile>), (StatusCode, String)> {
+    req.validate().map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let res = sqlx::query(
+        "INSERT INTO settings_profiles
+           (name, team, provider, model, temperature, top_p, system_prompt, response_prompt, allowed_endpoints)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&req.name)
+    .bind(&req.team)
+    .bind(&req.provider)
+    .bind(&req.model)
+    .bind(req.temperature)
+    .bind(req.top_p)
+    .bind(&req.system_prompt)
+    .bind(&req.response_prompt)
+    .bind(req.allowed_endpoints.as_ref().map(|e| serde_json::json!(e)))
+    .execute(&pool)
+    .await;
+
+    match res {
+        Ok(_) => {}
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return Err((
+                StatusCode::CONFLICT,
+                format!("profile {} already exists", req.name),
+            ));
+        }
+        Err(e) => return Err(internal_error(e)),
+    }
+
+    let created = profiles::get(&pool, &req.name)
+        .await
+        .map_err(internal_error)?
+        .ok_or_else(|| internal_error("profile vanished after insert"))?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// PUT /api/profiles/:name — ganti seluruh isi profil (nama tetap dari path)
+pub async fn update_profile(
+    State(pool): State<MySqlPool>,
+    Path(name): Path<String>,
+    Json(mut req): Json<ProfileReq>,
+) -> Result<Json<SettingsProfile>, (StatusCode, String)> {
+    req.name = name.clone();
+    req.validate().map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    sqlx::query(
+        "UPDATE settings_profiles SET team = ?, provider = ?, model = ?, temperature = ?, top_p = ?,
+           system_prompt = ?, response_prompt = ?, allowed_endpoints = ?
+         WHERE name = ?",
+    )
+    .bind(&req.team)
+    .bind(&req.provider)
+    .bind(&req.model)
+    .bind(req.temperature)
+    .bind(req.top_p)
+    .bind(&req.system_prompt)
+    .bind(&req.response_prompt)
+    .bind(req.allowed_endpoints.as_ref().map(|e| serde_json::json!(e)))
+    .bind(&name)
+    .execute(&pool)
+    .await
+    .map_err(internal_error)?;
+
+    // rows_affected = 0 juga kalau isinya sama persis → cek keberadaan via SELECT
+    profiles::get(&pool, &name)
+        .await
+        .map_err(internal_error)?
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, format!("profile {name} not found")))
+}
+
+/// DELETE /api/profiles/:name — user yang memakainya kembali ke settings global
+pub async fn delete_profile(
+    State(pool): State<MySqlPool>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let res = sqlx::query("DELETE FROM settings_profiles WHERE name = ?")
+        .bind(&name)
+        .execute(&pool)
+        .await
+        .map_err(internal_error)?;
+    if res.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, format!("profile {name} not found")));
+    }
+
+    sqlx::query("UPDATE users SET settings_profile = NULL WHERE settings_profile = ?")
+        .bind(&name)
+        .execute(&pool)
+        .await
+        .map_err(internal_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// PUT /api/users/:user_id/profile — profil default user (null = settings global)
+pub async fn set_user_profile(
+    State(pool): State<MySqlPool>,
+    Path(user_id): Path<String>,
+    Json(req): Json<UserProfileReq>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    if let Some(name) = &req.profile {
+        if profiles::get(&pool, name)
+            .await
+            .map_err(internal_error)?
+            .is_none()
+        {
+            return Err((StatusCode::NOT_FOUND, format!("profile {name} not found")));
+        }
+    }
+
+    let res = sqlx::query("UPDATE users SET settings_profile = ? WHERE username = ? OR email = ?")
+        .bind(&req.profile)
+        .bind(&user_id)
+        .bind(&user_id)
+        .execute(&pool)
+        .await
+        .map_err(internal_error)?;
+    if res.rows_affected() == 0 {
+        let (exists,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM users WHERE username = ? OR email = ?")
+                .bind(&user_id)
+                .bind(&user_id)
+                .fetch_one(&pool)
+                .await
+                .map_err(internal_error)?;
+        if exists == 0 {
+            return Err((StatusCode::NOT_FOUND, format!("user {user_id} not found")));
+        }
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
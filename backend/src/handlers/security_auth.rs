@@ -1,26 +1,106 @@
 // backend/src/handlers/security_auth.rs
-// backend/src/handlers/security_auth.rs
 
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    Json,
+};
+
+use crate::adapters::{security_auth::SecurityAuth, AdapterError, Params, Registry};
+use crate::config::SharedConfig;
+use crate::fetch::trusted_role;
 use crate::router::Range;
-use crate::util::now_gmt8;
-use axum::{extract::Query, Json};
-use serde::Serialize;
-
-#[derive(Serialize)]
-pub struct SecurityAuthDummy {
-    adapter: &'static str,
-    service: &'static str,
-    event: &'static str,
-    error_rate: f32, // error rate dalam persen
-    checked_at: String,
+
+/// GET /api/security-auth
+///
+/// Username/IP dimasking sesuai `[pii_masking]` untuk role yang diteruskan
+/// pipeline (lookup users.role di server, dibuktikan token internal). Panggilan
+/// langsung dari klien selalu mendapat level paling ketat.
+pub async fn get_security(
+    State(adapters): State<Registry>,
+    State(config): State<SharedConfig>,
+    headers: HeaderMap,
+    Query(q): Query<Range>,
+    Query(params): Query<Params>,
+) -> Result<Json<SecurityAuth>, AdapterError> {
+    let level = config
+        .current()
+        .pii_masking
+        .level_for(trusted_role(&headers));
+
+    let adapters = adapters.current();
+    let mut out = adapters.security_auth.fetch(&q, &params).await?;
+    out.mask(level);
+    Ok(Json(out))
 }
 
-pub async fn get_security(Query(_q): Query<Range>) -> Json<SecurityAuthDummy> {
-    Json(SecurityAuthDummy {
-        adapter: "security_auth",
-        service: "api-gateway-production",
-        event: "failed_login",
-        error_rate: 2.35, // dummy: 2.35% error rate sekarang
-        checked_at: now_gmt8().to_rfc3339(),
-    })
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::adapters::tests::lazy_pool;
+    use crate::config::Config;
+    use crate::fetch::{internal_headers, INTERNAL_TOKEN_HEADER, ROLE_HEADER};
+    use crate::router::AppState;
+
+    async fn call(headers: &[(&str, String)]) -> serde_json::Value {
+        let config = Config::default();
+        let pool = lazy_pool();
+        let state = AppState {
+            adapters: Registry::new(&config, pool.clone()),
+            config: SharedConfig::new(config),
+            pool,
+        };
+        let app = Router::new()
+            .route("/api/security-auth", get(get_security))
+            .with_state(state);
+
+        let mut req = Request::get("/api/security-auth");
+        for (k, v) in headers {
+            req = req.header(*k, v);
+        }
+        let resp = app.oneshot(req.body(Body::empty()).unwrap()).await.unwrap();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_spoofed_role_headers_keep_full_masking() {
+        let out = call(&[
+            ("x-user-id", "admin".into()),
+            (ROLE_HEADER, "admin".into()),
+            (INTERNAL_TOKEN_HEADER, "guessed".into()),
+        ])
+        .await;
+        assert_eq!(out["masking"], "full");
+        assert!(out["top_ips"][0]["key"]
+            .as_str()
+            .unwrap()
+            .starts_with("ip-"));
+        assert!(out["top_users"][0]["key"]
+            .as_str()
+            .unwrap()
+            .starts_with("user-"));
+    }
+
+    #[tokio::test]
+    async fn test_trusted_role_selects_masking_level() {
+        let admin = call(&internal_headers("admin")).await;
+        assert_eq!(admin["masking"], "none");
+        let ip = admin["top_ips"][0]["key"].as_str().unwrap();
+        assert!(ip.parse::<std::net::IpAddr>().is_ok(), "{ip}");
+
+        let engineer = call(&internal_headers("engineer")).await;
+        assert_eq!(engineer["masking"], "partial");
+        let ip = engineer["top_ips"][0]["key"].as_str().unwrap();
+        assert!(ip.ends_with(".x") || ip.ends_with("::x"), "{ip}");
+        assert!(engineer["top_users"][0]["key"]
+            .as_str()
+            .unwrap()
+            .contains("***"));
+    }
 }
@@ -0,0 +1,96 @@
+// backend/src/handlers/sessions.rs
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlPool, QueryBuilder};
+
+use super::chat_logs::CHAT_LOG_COLUMNS;
+use super::internal_error;
+use crate::models::{ChatLog, ChatSession};
+
+#[derive(Debug, Deserialize)]
+pub struct SessionListQuery {
+    pub user_id: Option<String>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionDetailResp {
+    #[serde(flatten)]
+    pub session: ChatSession,
+    pub history: Vec<ChatLog>,
+}
+
+const SESSION_SELECT: &str = "SELECT s.id, s.user_id, s.title, \
+     (SELECT COUNT(*) FROM chat_logs c WHERE c.session_id = s.id) AS turns, \
+     s.created_at, s.updated_at FROM chat_sessions s";
+
+/// GET /api/sessions?user_id=admin&limit=20 — terbaru dulu
+pub async fn list_sessions(
+    State(pool): State<MySqlPool>,
+    Query(q): Query<SessionListQuery>,
+) -> Result<Json<Vec<ChatSession>>, (StatusCode, String)> {
+    let limit = q.limit.unwrap_or(20).clamp(1, 200);
+    let offset = q.offset.unwrap_or(0);
+
+    let mut qb = QueryBuilder::new(format!("{SESSION_SELECT} WHERE 1=1"));
+    if let Some(u) = &q.user_id {
+        qb.push(" AND s.user_id = ").push_bind(u.clone());
+    }
+    qb.push(" ORDER BY s.updated_at DESC LIMIT ")
+        .push_bind(limit)
+        .push(" OFFSET ")
+        .push_bind(offset);
+
+    let items: Vec<ChatSession> = qb
+        .build_query_as()
+        .fetch_all(&pool)
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(items))
+}
+
+/// GET /api/sessions/:id — session + semua giliran (urut lama → baru)
+pub async fn get_session(
+    State(pool): State<MySqlPool>,
+    Path(id): Path<String>,
+) -> Result<Json<SessionDetailResp>, (StatusCode, String)> {
+    let session: Option<ChatSession> = sqlx::query_as(&format!("{SESSION_SELECT} WHERE s.id = ?"))
+        .bind(&id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(internal_error)?;
+    let session = session.ok_or((StatusCode::NOT_FOUND, format!("session {id} not found")))?;
+
+    let history: Vec<ChatLog> = sqlx::query_as(&format!(
+        "SELECT {CHAT_LOG_COLUMNS} FROM chat_logs WHERE session_id = ? ORDER BY id"
+    ))
+    .bind(&id)
+    .fetch_all(&pool)
+    .await
+    .map_err(internal_error)?;
+
+    Ok(Json(SessionDetailResp { session, history }))
+}
+
+/// DELETE /api/sessions/:id — chat_logs tetap ada (audit), session_id-nya di-NULL-kan (FK)
+pub async fn delete_session(
+    State(pool): State<MySqlPool>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let res = sqlx::query("DELETE FROM chat_sessions WHERE id = ?")
+        .bind(&id)
+        .execute(&pool)
+        .await
+        .map_err(internal_error)?;
+
+    if res.rows_affected() == 0 {
+        return Err((StatusCode::NOT_FOUND, format!("session {id} not found")));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,31 +1,215 @@
 // backend/src/handlers/settings.rs
-use axum::{extract::State, Json};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
 use serde::{Deserialize, Serialize};
 use sqlx::MySqlPool;
 
+use super::internal_error;
+use crate::config::SharedConfig;
+use crate::models::{Settings, SettingsRevision};
+use crate::sampling::SamplingBounds;
+use crate::settings_store::{self, NewSettings};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UiSettings {
     pub model: String,
     pub temperature: f32,
     pub top_p: f32,
+    /// Temperature planner; default 0 (routing deterministik), harus dalam `bounds`
+    #[serde(default)]
+    pub planner_temperature: f32,
+    /// Batas panjang jawaban; kosong = default model
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+    #[serde(default)]
+    pub stop: Option<Vec<String>>,
+    /// Batas override sampling per-request; kosong = [`SamplingBounds::default`]
+    #[serde(default)]
+    pub bounds: Option<SamplingBounds>,
     pub system_prompt: String,
+    #[serde(default)]
+    pub response_prompt: Option<String>,
+    #[serde(default = "default_streaming")]
     pub streaming: bool,
+    /// Penulis perubahan (audit)
+    #[serde(default)]
+    pub updated_by: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+fn default_streaming() -> bool {
+    true
+}
+
+impl UiSettings {
+    fn validate(&self) -> Result<(), String> {
+        if self.model.trim().is_empty() || self.model.len() > 64 {
+            return Err("model must be 1-64 chars".into());
+        }
+        if !(0.0..=2.0).contains(&self.temperature) {
+            return Err("temperature must be within 0.0..=2.0".into());
+        }
+        if !(0.0..=1.0).contains(&self.top_p) {
+            return Err("top_p must be within 0.0..=1.0".into());
+        }
+        let bounds = self.bounds.clone().unwrap_or_default();
+        let planner = self.planner_temperature as f64;
+        if !(bounds.temperature_min..=bounds.temperature_max).contains(&planner) {
+            return Err(format!(
+                "planner_temperature must be within {}..={}",
+                bounds.temperature_min, bounds.temperature_max
+            ));
+        }
+        if self.max_tokens == Some(0) {
+            return Err("max_tokens must be positive".into());
+        }
+        if let Some(stop) = &self.stop {
+            if stop.len() > 4 || stop.iter().any(|s| s.is_empty()) {
+                return Err("stop accepts at most 4 non-empty sequences".into());
+            }
+        }
+        if let Some(b) = &self.bounds {
+            if !(0.0 <= b.temperature_min
+                && b.temperature_min <= b.temperature_max
+                && b.temperature_max <= 2.0)
+            {
+                return Err("bounds: need 0.0 <= temperature_min <= temperature_max <= 2.0".into());
+            }
+            if !(0.0 <= b.top_p_min && b.top_p_min <= b.top_p_max && b.top_p_max <= 1.0) {
+                return Err("bounds: need 0.0 <= top_p_min <= top_p_max <= 1.0".into());
+            }
+            if b.max_tokens_limit == 0 || !(1..=4).contains(&b.max_stop_sequences) {
+                return Err(
+                    "bounds: max_tokens_limit must be positive, max_stop_sequences 1..=4".into(),
+                );
+            }
+        }
+        if self.system_prompt.trim().is_empty() {
+            return Err("system_prompt must not be empty".into());
+        }
+        Ok(())
+    }
 }
 
-pub async fn get_settings() -> Json<UiSettings> {
-    Json(UiSettings {
-        model: "gpt-4o-mini".into(),
-        temperature: 0.2,
-        top_p: 0.9,
-        system_prompt: "You are an MCP intent router...".into(),
-        streaming: true,
-    })
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub struct RollbackReq {
+    pub updated_by: Option<String>,
+}
+
+/// GET /api/settings — settings aktif (versi, penulis & waktu update)
+pub async fn get_settings(
+    State(pool): State<MySqlPool>,
+    State(config): State<SharedConfig>,
+) -> Json<Settings> {
+    Json(
+        settings_store::active(&pool, &config.current())
+            .await
+            .as_ref()
+            .clone(),
+    )
+}
+
+/// POST /api/settings — simpan sebagai versi baru
 pub async fn update_settings(
-    State(_pool): State<MySqlPool>,
+    State(pool): State<MySqlPool>,
     Json(payload): Json<UiSettings>,
-) -> Json<UiSettings> {
-    // PoC: simpan ke DB kalau mau; sekarang echo balik
-    Json(payload)
+) -> Result<Json<Settings>, (StatusCode, String)> {
+    payload
+        .validate()
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let new = NewSettings {
+        system_prompt: payload.system_prompt,
+        response_prompt: payload.response_prompt.filter(|p| !p.trim().is_empty()),
+        model: payload.model.trim().to_string(),
+        temperature: payload.temperature as f64,
+        top_p: payload.top_p as f64,
+        planner_temperature: payload.planner_temperature as f64,
+        max_tokens: payload.max_tokens,
+        stop: payload.stop.filter(|s| !s.is_empty()),
+        bounds: payload.bounds,
+        streaming: payload.streaming,
+    };
+    let saved = settings_store::save(
+        &pool,
+        &new,
+        payload.updated_by.as_deref(),
+        payload.note.as_deref(),
+    )
+    .await
+    .map_err(internal_error)?;
+    Ok(Json(saved))
+}
+
+/// GET /api/settings/history?limit=20 — revisi terbaru dulu
+pub async fn settings_history(
+    State(pool): State<MySqlPool>,
+    Query(q): Query<HistoryQuery>,
+) -> Result<Json<Vec<SettingsRevision>>, (StatusCode, String)> {
+    let limit = q.limit.unwrap_or(20).clamp(1, 200);
+    let revs = settings_store::revisions(&pool, limit, q.offset.unwrap_or(0))
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(revs))
+}
+
+/// POST /api/settings/rollback/:version — aktifkan lagi isi revisi lama
+pub async fn rollback_settings(
+    State(pool): State<MySqlPool>,
+    Path(version): Path<i32>,
+    body: Option<Json<RollbackReq>>,
+) -> Result<Json<Settings>, (StatusCode, String)> {
+    let req = body.map(|Json(b)| b).unwrap_or_default();
+    settings_store::rollback(&pool, version, req.updated_by.as_deref())
+        .await
+        .map_err(internal_error)?
+        .map(Json)
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("settings version {version} not found"),
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_bounds() {
+        let mut s: UiSettings = serde_json::from_str(
+            r#"{"model":"gpt-4o-mini","temperature":0.2,"top_p":0.9,"system_prompt":"route"}"#,
+        )
+        .unwrap();
+        assert!(s.streaming);
+        assert!(s.validate().is_ok());
+
+        s.temperature = 2.5;
+        assert!(s.validate().is_err());
+        s.temperature = 0.2;
+        s.top_p = -0.1;
+        assert!(s.validate().is_err());
+        s.top_p = 0.9;
+        s.planner_temperature = 1.5;
+        assert!(s.validate().is_err());
+        s.planner_temperature = 0.0;
+
+        s.bounds = Some(SamplingBounds {
+            temperature_min: 0.8,
+            temperature_max: 0.5,
+            ..Default::default()
+        });
+        assert!(s.validate().is_err());
+        s.bounds = Some(SamplingBounds::default());
+        assert!(s.validate().is_ok());
+    }
 }
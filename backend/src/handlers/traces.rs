@@ -0,0 +1,109 @@
+// backend/src/handlers/traces.rs
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Serialize;
+use sqlx::MySqlPool;
+
+use super::internal_error;
+use crate::models::DebugTrace;
+
+#[derive(Debug, Serialize)]
+pub struct TracePhase {
+    pub phase: String,
+    pub payload: Option<serde_json::Value>,
+    pub at: String,
+    pub elapsed_ms: Option<u32>,
+    pub delta_ms: Option<u32>, // selisih dengan fase sebelumnya
+}
+
+#[derive(Debug, Serialize)]
+pub struct TraceTimeline {
+    pub trace_id: String,
+    pub total_ms: Option<u32>,
+    pub phases: Vec<TracePhase>,
+}
+
+fn build_timeline(trace_id: String, rows: Vec<DebugTrace>) -> TraceTimeline {
+    let mut prev: Option<u32> = None;
+    let mut phases = Vec::with_capacity(rows.len());
+    for r in rows {
+        let delta_ms = match (prev, r.elapsed_ms) {
+            (Some(p), Some(e)) => Some(e.saturating_sub(p)),
+            (None, e) => e,
+            _ => None,
+        };
+        if r.elapsed_ms.is_some() {
+            prev = r.elapsed_ms;
+        }
+        phases.push(TracePhase {
+            phase: r.phase,
+            payload: r.payload,
+            at: r.created_at.to_rfc3339(),
+            elapsed_ms: r.elapsed_ms,
+            delta_ms,
+        });
+    }
+    TraceTimeline {
+        trace_id,
+        total_ms: prev,
+        phases,
+    }
+}
+
+/// GET /api/traces/:trace_id — timeline fase SSE (urut) untuk satu x-request-id
+pub async fn get_trace(
+    State(pool): State<MySqlPool>,
+    Path(trace_id): Path<String>,
+) -> Result<Json<TraceTimeline>, (StatusCode, String)> {
+    let rows: Vec<DebugTrace> = sqlx::query_as(
+        "SELECT id, trace_id, phase, payload, elapsed_ms, created_at
+         FROM debug_traces WHERE trace_id = ? ORDER BY id",
+    )
+    .bind(&trace_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(internal_error)?;
+
+    if rows.is_empty() {
+        return Err((StatusCode::NOT_FOUND, format!("trace {trace_id} not found")));
+    }
+
+    Ok(Json(build_timeline(trace_id, rows)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn row(id: i64, phase: &str, elapsed_ms: Option<u32>) -> DebugTrace {
+        DebugTrace {
+            id,
+            trace_id: "t-1".into(),
+            phase: phase.into(),
+            payload: None,
+            elapsed_ms,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_build_timeline_deltas() {
+        let tl = build_timeline(
+            "t-1".into(),
+            vec![
+                row(1, "received", Some(0)),
+                row(2, "route_planned", Some(850)),
+                row(3, "legacy", None),
+                row(4, "done", Some(2100)),
+            ],
+        );
+        let deltas: Vec<_> = tl.phases.iter().map(|p| p.delta_ms).collect();
+        assert_eq!(deltas, vec![Some(0), Some(850), None, Some(1250)]);
+        assert_eq!(tl.total_ms, Some(2100));
+    }
+}
@@ -0,0 +1,85 @@
+// backend/src/handlers/usage.rs
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlPool, QueryBuilder};
+
+use super::internal_error;
+
+#[derive(Debug, Deserialize)]
+pub struct UsageQuery {
+    pub date_from: Option<String>, // YYYY-MM-DD (inklusif)
+    pub date_to: Option<String>,   // YYYY-MM-DD (inklusif)
+    pub user_id: Option<String>,
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct UsageRow {
+    pub day: String,
+    pub user_id: Option<String>,
+    pub model: String,
+    pub requests: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UsageResp {
+    pub rows: Vec<UsageRow>,
+    pub total_tokens: i64,
+    pub total_cost_usd: f64,
+}
+
+/// GET /api/usage?date_from=2025-09-01&date_to=2025-09-30&user_id=admin&model=gpt-4o-mini
+/// Aggregate per user / hari / model.
+pub async fn get_usage(
+    State(pool): State<MySqlPool>,
+    Query(q): Query<UsageQuery>,
+) -> Result<Json<UsageResp>, (axum::http::StatusCode, String)> {
+    let mut qb = QueryBuilder::new(
+        "SELECT DATE_FORMAT(created_at, '%Y-%m-%d') AS day, user_id, model, \
+         COUNT(DISTINCT request_id) AS requests, \
+         CAST(SUM(prompt_tokens) AS SIGNED) AS prompt_tokens, \
+         CAST(SUM(completion_tokens) AS SIGNED) AS completion_tokens, \
+         CAST(SUM(cost_usd) AS DOUBLE) AS cost_usd \
+         FROM llm_usage WHERE 1=1",
+    );
+    if let Some(df) = &q.date_from {
+        qb.push(" AND created_at >= ").push_bind(df.clone());
+    }
+    if let Some(dt) = &q.date_to {
+        qb.push(" AND created_at < DATE_ADD(")
+            .push_bind(dt.clone())
+            .push(", INTERVAL 1 DAY)");
+    }
+    if let Some(u) = &q.user_id {
+        qb.push(" AND user_id = ").push_bind(u.clone());
+    }
+    if let Some(m) = &q.model {
+        qb.push(" AND model = ").push_bind(m.clone());
+    }
+    qb.push(" GROUP BY day, user_id, model ORDER BY day DESC, user_id, model");
+
+    let rows: Vec<UsageRow> = qb
+        .build_query_as()
+        .fetch_all(&pool)
+        .await
+        .map_err(internal_error)?;
+
+    let total_tokens = rows
+        .iter()
+        .map(|r| r.prompt_tokens + r.completion_tokens)
+        .sum();
+    let total_cost_usd = rows.iter().map(|r| r.cost_usd).sum();
+
+    Ok(Json(UsageResp {
+        rows,
+        total_tokens,
+        total_cost_usd,
+    }))
+}
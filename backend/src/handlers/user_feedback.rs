@@ -1,21 +1,19 @@
 // backend/src/handlers/user_feedback.rs
 
-use crate::router::Range;
-use crate::util::now_gmt8;
-use axum::{extract::Query, Json};
-use serde::Serialize;
+use axum::{
+    extract::{Query, State},
+    Json,
+};
 
-#[derive(Serialize)]
-pub struct UserFeedbackDummy {
-    adapter: &'static str,
-    source: &'static str,
-    checked_at: String,
-}
+use crate::adapters::{user_feedback::UserFeedback, AdapterError, Params, Registry};
+use crate::router::Range;
 
-pub async fn get_feedback(Query(_q): Query<Range>) -> Json<UserFeedbackDummy> {
-    Json(UserFeedbackDummy {
-        adapter: "user_feedback",
-        source: "playstore",
-        checked_at: now_gmt8().to_rfc3339(),
-    })
+/// GET /api/user-feedback
+pub async fn get_feedback(
+    State(adapters): State<Registry>,
+    Query(q): Query<Range>,
+    Query(params): Query<Params>,
+) -> Result<Json<UserFeedback>, AdapterError> {
+    let adapters = adapters.current();
+    adapters.user_feedback.fetch(&q, &params).await.map(Json)
 }
@@ -2,15 +2,31 @@
 mod config;
 mod db;
 mod handlers;
+#[allow(dead_code)] // belum dipakai router (Responses API variant)
 mod intent; // jika ada
 mod models;
 mod router;
 mod util;
 
+mod adapters;
+mod answer;
+mod chat_store;
 mod fetch;
 mod mcp;
+mod pipeline;
+mod profiles;
+mod prompts;
+mod sampling;
+mod services;
+mod sessions;
+mod settings_store;
+mod stream_buffer;
+mod traces;
+mod usage;
 
 use axum::{http, routing::get, Router};
+use config::{Config, SharedConfig};
+use router::AppState;
 use std::time::Duration;
 use tokio::net::TcpListener;
 use tower::ServiceBuilder;
@@ -24,13 +40,34 @@ use util::logging::init_tracing;
 
 #[tokio::main]
 async fn main() {
-    init_tracing();
+    let config = match Config::load() {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("❌ {e}");
+            std::process::exit(2);
+        }
+    };
+    init_tracing(&config);
 
-    let pool = db::init_db().await.expect("DB connection failed");
+    let pool = db::init_db(&config).await.expect("DB connection failed");
+    settings_store::seed_initial_revision(&pool, &config).await;
+    let port = config.port;
+    let registry = adapters::Registry::new(&config, pool.clone());
+    let shared = SharedConfig::new(config);
+    let on_reload = {
+        let registry = registry.clone();
+        move |cfg: &Config| registry.rebuild(cfg)
+    };
+    config::spawn_reload_on_sighup(shared.clone(), on_reload);
+    let state = AppState {
+        pool: pool.clone(),
+        config: shared,
+        adapters: registry,
+    };
 
     let app = Router::new()
         .route("/health", get(|| async { "ok" }))
-        .merge(router::app_routes(pool.clone()))
+        .merge(router::app_routes(state))
         .layer(CorsLayer::new().allow_origin(Any).allow_methods(Any).allow_headers(Any))
         .layer(PropagateRequestIdLayer::x_request_id())
         .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
@@ -53,7 +90,6 @@ async fn main() {
             )
         );
 
-    let port = std::env::var("PORT").unwrap_or_else(|_| "8080".to_string());
     let addr = format!("0.0.0.0:{port}");
     tracing::info!("🚀 SMRT MCP Backend on {}", addr);
 
@@ -21,6 +21,24 @@ impl RouterPlan {
     }
 }
 
+/// Endpoint internal yang boleh dipilih planner / override client
+pub const ENDPOINTS: &[&str] = &[
+    "/api/gitlab-ci",
+    "/api/runtime-logs",
+    "/api/observability",
+    "/api/cloud-mon",
+    "/api/db-perf",
+    "/api/mobile-telemetry",
+    "/api/security-auth",
+    "/api/incident-metrics",
+    "/api/user-feedback",
+    "/api/data-integration-bi",
+];
+
+pub fn is_known_endpoint(ep: &str) -> bool {
+    ENDPOINTS.contains(&ep)
+}
+
 fn infer_service(user_text: &str) -> Option<String> {
     let t = user_text.to_lowercase();
     for key in [
@@ -64,6 +82,14 @@ pub fn heuristic_plan(user_text: &str) -> RouterPlan {
             "incident_review",
             vec!["/api/incident-metrics", "/api/runtime-logs"],
         )
+    } else if ["crash", "anr", "mobile", "android", "ios"]
+        .iter()
+        .any(|w| {
+            t.split(|c: char| !c.is_alphanumeric())
+                .any(|tok| tok.starts_with(w))
+        })
+    {
+        RouterPlan::new("mobile_stability", vec!["/api/mobile-telemetry"])
     } else if t.contains("feedback") || (t.contains("user") && t.contains("report")) {
         RouterPlan::new("user_feedback_review", vec!["/api/user-feedback"])
     } else {
@@ -87,7 +113,16 @@ struct OaiPlan {
     params: HashMap<String, String>,
 }
 
-pub fn intent_prompt(system_hint: &str, user_text: &str) -> String {
+/// `history` = ringkasan giliran sebelumnya (kosong untuk pertanyaan pertama)
+pub fn intent_prompt(system_hint: &str, history: &str, user_text: &str) -> String {
+    let history = if history.trim().is_empty() {
+        String::new()
+    } else {
+        format!(
+            "Conversation so far (oldest first). The last user message may be a follow-up; \
+             reuse service/date params from earlier turns unless the user changes them:\n{history}\n"
+        )
+    };
     format!(
         r#"You are a router. Return ONLY a compact JSON with fields: intent (string), endpoints (array of strings), params (object).
 Available endpoints:
@@ -96,8 +131,8 @@ Available endpoints:
 - "/api/observability"          : SLO, error_rate, p95 latency
 - "/api/cloud-mon"              : cloud infra metrics
 - "/api/db-perf"                : db query perf & locks
-- "/api/mobile-telemetry"       : mobile client telemetry
-- "/api/security-auth"          : auth failures, lockouts
+- "/api/mobile-telemetry"       : crash-free sessions, crash signatures, ANR rate per app version/OS/platform
+- "/api/security-auth"          : failed-login rate, lockouts, top IPs/users, credential stuffing
 - "/api/incident-metrics"       : incidents, MTTR, rollback
 - "/api/user-feedback"          : NPS, CSAT, user tickets
 - "/api/data-integration-bi"    : BI joins & KPIs
@@ -108,7 +143,7 @@ Rules:
 
 System hint: {system_hint}
 
-User: {user_text}
+{history}User: {user_text}
 "#
     )
 }
@@ -3,9 +3,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct ChatLog {
     pub id: i64,
+    pub request_id: Option<String>,
+    pub session_id: Option<String>,
     pub user_id: Option<String>,
     pub user_query: String,
     pub detected_intent: Option<String>,
@@ -14,22 +16,87 @@ pub struct ChatLog {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ApiResult {
+    pub id: i64,
+    pub chat_log_id: Option<i64>,
+    pub endpoint: String,
+    pub status: String,
+    pub request_params: Option<serde_json::Value>,
+    pub response_data: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ChatSession {
+    pub id: String,
+    pub user_id: Option<String>,
+    pub title: Option<String>,
+    pub turns: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct DebugTrace {
     pub id: i64,
     pub trace_id: String,
     pub phase: String,
-    pub payload: serde_json::Value,
+    pub payload: Option<serde_json::Value>,
+    pub elapsed_ms: Option<u32>,
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Settings aktif (baris settings id=1)
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Settings {
-    pub id: i32,
     pub system_prompt: String,
     pub response_prompt: Option<String>,
     pub model: String,
-    pub temperature: f32,
-    pub top_p: f32,
-    pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub temperature: f64,
+    pub top_p: f64,
+    /// Temperature langkah planner (routing JSON)
+    pub planner_temperature: f64,
+    pub max_tokens: Option<i32>,
+    pub stop: Option<sqlx::types::Json<Vec<String>>>,
+    pub bounds: Option<sqlx::types::Json<crate::sampling::SamplingBounds>>,
+    pub streaming: bool,
+    pub version: i32,
+    pub updated_by: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SettingsRevision {
+    pub id: i64,
+    pub version: i32,
+    pub system_prompt: String,
+    pub response_prompt: Option<String>,
+    pub model: String,
+    pub temperature: f64,
+    pub top_p: f64,
+    pub planner_temperature: f64,
+    pub max_tokens: Option<i32>,
+    pub stop: Option<sqlx::types::Json<Vec<String>>>,
+    pub bounds: Option<sqlx::types::Json<crate::sampling::SamplingBounds>>,
+    pub streaming: bool,
+    pub author: Option<String>,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SettingsProfile {
+    pub id: i64,
+    pub name: String,
+    pub team: Option<String>,
+    pub provider: String,
+    pub model: Option<String>,
+    pub temperature: Option<f64>,
+    pub top_p: Option<f64>,
+    pub system_prompt: Option<String>,
+    pub response_prompt: Option<String>,
+    pub allowed_endpoints: Option<sqlx::types::Json<Vec<String>>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
@@ -0,0 +1,778 @@
+// backend/src/pipeline.rs
+
+use futures_util::{Stream, StreamExt, TryStreamExt};
+use regex::Regex;
+use serde::Deserialize;
+use sqlx::MySqlPool;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+use crate::answer::{self, Finding, Severity, StructuredAnswer};
+use crate::chat_store;
+use crate::config::Config;
+use crate::fetch::fetch_one;
+use crate::handlers::chat::ChatRequest;
+use crate::mcp::{intent_prompt, parse_or_fallback, RouterPlan};
+use crate::profiles;
+use crate::prompts::{self, AnswerVars};
+use crate::sampling::Sampling;
+use crate::sessions;
+use crate::settings_store;
+use crate::traces::TraceRecorder;
+use crate::usage::{self, TokenUsage};
+
+/* ------------------------- Events ------------------------- */
+
+/// Event pipeline chat. Nama & payload di wire (SSE/WebSocket) lihat
+/// [`ChatEvent::name`] dan [`ChatEvent::data`].
+#[derive(Debug, Clone)]
+pub enum ChatEvent {
+    Received(String),
+    /// step = "plan" | "answer"
+    LlmStart(&'static str),
+    Clarify {
+        param: &'static str,
+        question: &'static str,
+    },
+    RoutePlanned(RouterPlan),
+    FetchProgress {
+        endpoint: String,
+        status: &'static str,
+    },
+    Joined(serde_json::Value),
+    Token(String),
+    /// Jawaban terstruktur: summary → finding (0..n) → next_actions
+    Summary {
+        summary: String,
+        severity: Severity,
+    },
+    Finding {
+        index: usize,
+        finding: Finding,
+    },
+    NextActions(Vec<String>),
+    Done,
+}
+
+impl ChatEvent {
+    pub fn token(s: impl Into<String>) -> Self {
+        Self::Token(s.into())
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Received(_) => "received",
+            Self::LlmStart(_) => "llm_start",
+            Self::Clarify { .. } => "clarify",
+            Self::RoutePlanned(_) => "route_planned",
+            Self::FetchProgress { .. } => "fetch_progress",
+            Self::Joined(_) => "joined",
+            Self::Token(_) => "token",
+            Self::Summary { .. } => "summary",
+            Self::Finding { .. } => "finding",
+            Self::NextActions(_) => "next_actions",
+            Self::Done => "done",
+        }
+    }
+
+    pub fn data(&self) -> String {
+        match self {
+            Self::Received(s) | Self::Token(s) => s.clone(),
+            Self::LlmStart(step) => step.to_string(),
+            Self::Clarify { param, question } => {
+                serde_json::json!({ "param": param, "question": question }).to_string()
+            }
+            Self::RoutePlanned(plan) => serde_json::to_string(plan).unwrap_or_else(|_| "{}".into()),
+            Self::FetchProgress { endpoint, status } => {
+                serde_json::json!({ "endpoint": endpoint, "status": status }).to_string()
+            }
+            Self::Joined(v) => serde_json::to_string_pretty(v).unwrap_or_else(|_| "{}".into()),
+            Self::Summary { summary, severity } => {
+                serde_json::json!({ "summary": summary, "severity": severity }).to_string()
+            }
+            Self::Finding { index, finding } => {
+                let mut v = serde_json::json!(finding);
+                v["index"] = serde_json::json!(index);
+                v.to_string()
+            }
+            Self::NextActions(actions) => serde_json::json!(actions).to_string(),
+            Self::Done => "done".into(),
+        }
+    }
+}
+
+/// Hasil endpoint non-stream
+#[derive(Debug, Default)]
+pub struct CollectedReply {
+    pub reply: String,
+    pub structured: Option<StructuredAnswer>,
+}
+
+/// Kumpulkan event jadi jawaban utuh (endpoint non-stream)
+pub async fn collect_reply<S>(events: S) -> Result<CollectedReply, axum::Error>
+where
+    S: Stream<Item = Result<ChatEvent, axum::Error>>,
+{
+    futures_util::pin_mut!(events);
+    let mut out = CollectedReply::default();
+    while let Some(ev) = events.next().await {
+        match ev? {
+            ChatEvent::Token(t) => out.reply.push_str(&t),
+            ChatEvent::Summary { summary, severity } => {
+                out.structured = Some(StructuredAnswer {
+                    summary,
+                    severity,
+                    ..Default::default()
+                });
+            }
+            ChatEvent::Finding { finding, .. } => {
+                if let Some(sa) = out.structured.as_mut() {
+                    sa.findings.push(finding);
+                }
+            }
+            ChatEvent::NextActions(actions) => {
+                if let Some(sa) = out.structured.as_mut() {
+                    sa.next_actions = actions;
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(sa) = &out.structured {
+        out.reply = sa.render();
+    }
+    Ok(out)
+}
+
+/* ------------------------- Helpers ------------------------- */
+
+pub(crate) fn build_client(
+    cfg: &Config,
+    timeout: Duration,
+) -> Result<reqwest::Client, axum::Error> {
+    let mut b = reqwest::Client::builder()
+        .timeout(timeout)
+        .connect_timeout(Duration::from_secs(10))
+        .pool_idle_timeout(Duration::from_secs(90))
+        .tcp_keepalive(Some(Duration::from_secs(30)))
+        .user_agent("smrt-mcp-backend/0.1 (+github.com/your-org)");
+
+    // proxy (URL sudah divalidasi saat config dimuat)
+    if let Some(p) = &cfg.https_proxy {
+        b = b.proxy(reqwest::Proxy::https(p).map_err(to_axum_error)?);
+    }
+    if let Some(p) = &cfg.http_proxy {
+        b = b.proxy(reqwest::Proxy::http(p).map_err(to_axum_error)?);
+    }
+
+    // (opsional) jika butuh: paksa HTTP/1.1 untuk menghindari h2 handshake isu
+    if cfg.force_http1 {
+        b = b.http1_only();
+    }
+
+    b.build().map_err(to_axum_error)
+}
+
+/// Client without proxy — for internal loopback calls
+fn build_client_no_proxy(timeout: Duration) -> Result<reqwest::Client, axum::Error> {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .no_proxy()
+        .build()
+        .map_err(to_axum_error)
+}
+
+fn to_axum_error<E>(e: E) -> axum::Error
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let mut src: &(dyn std::error::Error + 'static) = &e;
+    tracing::error!("stream error: {}", src);
+    while let Some(cause) = src.source() {
+        tracing::error!("  caused by: {}", cause);
+        src = cause;
+    }
+    axum::Error::new(e)
+}
+
+/// Local fallback renderer (no LLM): hitung level log + 10 baris terakhir
+/// dari hasil endpoint pertama, dipakai saat upstream tidak bisa dipanggil.
+fn render_log_fallback(
+    joined_json: &serde_json::Value,
+    header: String,
+    footer: String,
+) -> Vec<String> {
+    let logs = joined_json["results"]
+        .get(0)
+        .and_then(|x| x.get("data"))
+        .and_then(|d| d.get("logs"))
+        .and_then(|l| l.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    let mut counts = std::collections::BTreeMap::<String, usize>::new();
+    for item in &logs {
+        let lvl = item
+            .get("level")
+            .and_then(|v| v.as_str())
+            .unwrap_or("UNKNOWN")
+            .to_string();
+        *counts.entry(lvl).or_insert(0) += 1;
+    }
+
+    let mut out = vec![header];
+    for (lvl, c) in counts {
+        out.push(format!("• {lvl}: {c}\n"));
+    }
+
+    let start = logs.len().saturating_sub(10);
+    if !logs.is_empty() {
+        out.push("\nLast lines:\n".to_string());
+    }
+    for item in logs.iter().skip(start) {
+        let ts = item.get("ts").and_then(|v| v.as_str()).unwrap_or("-");
+        let lvl = item.get("level").and_then(|v| v.as_str()).unwrap_or("-");
+        let msg = item.get("message").and_then(|v| v.as_str()).unwrap_or("-");
+        out.push(format!("[{ts}] {lvl}: {msg}\n"));
+    }
+    out.push(footer);
+    out
+}
+
+/* ------------------------- Pipeline ------------------------- */
+
+/// Pertanyaan balik ke user saat plan butuh logs tapi service tidak diketahui
+const CLARIFY_SERVICE_QUESTION: &str =
+    "Which service should I look at (e.g. payments, auth-service, orders)?";
+const CLARIFY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Klarifikasi yang sedang ditunggu pipeline. Sender hanya ada antara event
+/// `clarify` dan jawaban pertama/timeout, jadi jawaban yang datang terlalu awal
+/// atau dobel ditolak, bukan diantrikan untuk pertanyaan berikutnya.
+#[derive(Clone, Default)]
+pub struct PendingClarification(Arc<Mutex<Option<oneshot::Sender<String>>>>);
+
+impl PendingClarification {
+    fn slot(&self) -> std::sync::MutexGuard<'_, Option<oneshot::Sender<String>>> {
+        self.0.lock().expect("clarification slot poisoned")
+    }
+
+    /// Mulai menunggu jawaban (dipanggil sebelum event `clarify` dikirim)
+    fn open(&self) -> oneshot::Receiver<String> {
+        let (tx, rx) = oneshot::channel();
+        *self.slot() = Some(tx);
+        rx
+    }
+
+    fn close(&self) {
+        self.slot().take();
+    }
+
+    /// Serahkan jawaban; false kalau pipeline sedang tidak menunggu klarifikasi
+    pub fn answer(&self, answer: String) -> bool {
+        self.slot().take().is_some_and(|tx| tx.send(answer).is_ok())
+    }
+}
+
+/// Dependensi satu eksekusi pipeline (trace id = req_id)
+pub struct PipelineCtx {
+    pub pool: MySqlPool,
+    pub cfg: Config,
+    pub base_url: String,
+    pub req_id: String,
+    pub tracer: TraceRecorder,
+}
+
+/// Pipeline chat: planner → fetch → jawaban LLM, sebagai urutan [`ChatEvent`].
+/// Dipakai semua transport (JSON, SSE, WebSocket); `clarifications` hanya ada
+/// untuk transport dua arah.
+pub fn run(
+    ctx: PipelineCtx,
+    q: ChatRequest,
+    clarifications: Option<PendingClarification>,
+) -> impl Stream<Item = Result<ChatEvent, axum::Error>> {
+    let PipelineCtx {
+        pool,
+        cfg,
+        base_url,
+        req_id,
+        tracer,
+    } = ctx;
+    let user_text = q.text.clone();
+
+    async_stream::try_stream! {
+        // model & prompt dari settings aktif (cache), sisanya dari env
+        let mut cfg = cfg;
+        let settings = settings_store::active(&pool, &cfg).await;
+        settings_store::apply(&mut cfg, &settings);
+        let profile = profiles::resolve(&pool, q.profile.as_deref(), q.user_id.as_deref()).await;
+        let profile = match profile {
+            Ok(p) => p,
+            Err(e) => {
+                tracer.record("done", serde_json::json!({ "outcome": "invalid_profile", "error": e }));
+                yield ChatEvent::token(format!("({e})"));
+                yield ChatEvent::Done;
+                return;
+            }
+        };
+        if let Some(p) = &profile {
+            profiles::apply(&mut cfg, p);
+        }
+        let model = cfg.model.clone();
+        let system_prompt = cfg.system_prompt.clone();
+
+        // received
+        let session_id = q.session_id.as_deref();
+        if let Err(e) = q.validate() {
+            tracer.record("done", serde_json::json!({ "outcome": "invalid_request", "error": e }));
+            yield ChatEvent::token(format!("({e})"));
+            yield ChatEvent::Done;
+            return;
+        }
+        // settings → profil → override request (dalam bounds admin)
+        let sampling = match Sampling::resolve(&settings, profile.as_ref(), &q.sampling_overrides()) {
+            Ok(s) => s,
+            Err(e) => {
+                tracer.record("done", serde_json::json!({ "outcome": "invalid_sampling", "error": e }));
+                yield ChatEvent::token(format!("({e})"));
+                yield ChatEvent::Done;
+                return;
+            }
+        };
+        // planner: settings.planner_temperature (default 0, dalam bounds admin)
+        let planner_temperature = Sampling::planner_temperature(&settings);
+        // structured selalu satu JSON utuh; selain itu ikut settings.streaming
+        let stream_answer = settings.streaming && !q.structured;
+        let history = match session_id {
+            Some(sid) => sessions::load_history(&pool, sid, cfg.history_token_budget, cfg.history_max_turns).await,
+            None => Vec::new(),
+        };
+
+        tracer.record("received", serde_json::json!({
+            "text": user_text,
+            "user_id": q.user_id,
+            "session_id": session_id,
+            "profile": q.profile,
+            "endpoints_override": q.endpoints,
+            "settings_version": settings.version,
+            "profile_applied": profile.as_ref().map(|p| &p.name),
+            "sampling": sampling,
+            "planner_temperature": planner_temperature,
+            "streaming": stream_answer,
+            "history_turns": history.len()
+        }));
+        yield ChatEvent::Received(user_text.clone());
+
+        if cfg.openai_api_key.trim().is_empty() {
+            tracer.record("done", serde_json::json!({ "outcome": "missing_api_key" }));
+            yield ChatEvent::token("(missing OPENAI_API_KEY)");
+            yield ChatEvent::Done;
+            return;
+        }
+
+        // Upstream client (may proxy) & internal client (no proxy)
+        let client_up = build_client(&cfg, Duration::from_secs(60))?;
+        let client_in = build_client_no_proxy(Duration::from_secs(15))?;
+
+        // llm_start(plan)
+        tracer.record("llm_start", serde_json::json!({ "step": "plan", "model": model }));
+        yield ChatEvent::LlmStart("plan");
+
+        // Planner
+        let plan_prompt_str = intent_prompt(&system_prompt, &sessions::planner_context(&history), &user_text);
+        let plan_body = serde_json::json!({
+            "model": model,
+            "messages": [
+                { "role": "system", "content": "Return JSON only. No prose." },
+                { "role": "user",   "content": plan_prompt_str }
+            ],
+            "temperature": planner_temperature
+        });
+
+        let plan_res = client_up
+            .post("https://api.openai.com/v1/chat/completions")
+            .bearer_auth(&cfg.openai_api_key)
+            .json(&plan_body)
+            .send()
+            .await;
+
+        let plan_text = match plan_res {
+            Ok(rsp) if rsp.status().is_success() => {
+                #[derive(Deserialize)] struct Choice { message: MsgOut }
+                #[derive(Deserialize)] struct MsgOut { content: String }
+                #[derive(Deserialize)] struct PlanResp { choices: Vec<Choice>, usage: Option<TokenUsage> }
+                let pr: PlanResp = rsp.json().await.map_err(to_axum_error)?;
+                if let Some(u) = &pr.usage {
+                    usage::record(&pool, &req_id, q.user_id.as_deref(), &model, "plan", u).await;
+                }
+                pr.choices.first().map(|c| c.message.content.clone()).unwrap_or_default()
+            },
+            Ok(rsp) => {
+                let st = rsp.status();
+                let body = rsp.text().await.unwrap_or_default();
+                tracing::warn!(%st, body = %body, "planner non-200; using heuristic");
+                String::new()
+            }
+            Err(e) => {
+                tracing::warn!("planner request failed: {e}; using heuristic");
+                String::new()
+            }
+        };
+
+        // build plan (fallback → heuristic)
+        let mut plan = parse_or_fallback(&plan_text, &user_text);
+        if let Some(df) = &q.date_from { plan.params.insert("date_from".into(), df.clone()); }
+        if let Some(dt) = &q.date_to   { plan.params.insert("date_to".into(),   dt.clone()); }
+        if let Some(tz) = &q.tz        { plan.params.insert("tz".into(),        tz.clone()); }
+
+        // Fallback regex service
+        if !plan.params.contains_key("service") {
+            let t = user_text.to_lowercase();
+            let regex_guess = Regex::new(r"([a-z0-9\-]+)\s+service").ok()
+                .and_then(|re| re.captures(&t).and_then(|c| c.get(1).map(|m| m.as_str().to_string())));
+            let simple_guess = if t.contains("payments") || t.contains("payment") {
+                Some("payments".to_string())
+            } else if t.contains("auth") {
+                Some("auth-service".to_string())
+            } else if t.contains("orders") || t.contains("order") {
+                Some("orders".to_string())
+            } else { None };
+            if let Some(svc) = simple_guess.or(regex_guess) {
+                plan.params.insert("service".into(), svc);
+            }
+        }
+
+        q.apply_endpoint_override(&mut plan);
+        if let Some(p) = &profile {
+            let explicit = q.endpoints.as_ref().is_some_and(|e| !e.is_empty());
+            if let Err(e) = p.restrict(&mut plan, explicit) {
+                tracer.record("done", serde_json::json!({ "outcome": "endpoint_not_allowed", "error": e }));
+                yield ChatEvent::token(format!("({e})"));
+                yield ChatEvent::Done;
+                return;
+            }
+        }
+
+        // Transport dua arah: tanya balik service kalau logs dibutuhkan tapi belum jelas
+        let needs_service = plan.endpoints.iter().any(|e| e == "/api/runtime-logs")
+            && !plan.params.contains_key("service");
+        if let (true, Some(pending)) = (needs_service, clarifications.as_ref()) {
+            tracer.record("clarify", serde_json::json!({ "param": "service" }));
+            let rx = pending.open();
+            yield ChatEvent::Clarify { param: "service", question: CLARIFY_SERVICE_QUESTION };
+            let res = tokio::time::timeout(CLARIFY_TIMEOUT, rx).await;
+            pending.close();
+            match res {
+                Ok(Ok(answer)) if !answer.trim().is_empty() => {
+                    plan.params.insert("service".into(), answer.trim().to_string());
+                }
+                _ => tracing::info!("no clarification received; continuing without service"),
+            }
+        }
+
+        // route_planned
+        tracer.record("route_planned", serde_json::json!({ "plan": plan, "planner_raw": plan_text }));
+        yield ChatEvent::RoutePlanned(plan.clone());
+        if let Some(sid) = session_id {
+            sessions::ensure(&pool, sid, q.user_id.as_deref(), &user_text).await;
+        }
+        let chat_log_id = chat_store::start(&pool, &req_id, session_id, q.user_id.as_deref(), &user_text, &plan).await;
+
+        // role dari tabel users, dipakai endpoint internal (masking PII) & response prompt
+        let user_role = match q.user_id.as_deref() {
+            Some(uid) => prompts::user_role(&pool, uid).await,
+            None => None,
+        };
+
+        // fetch_progress (internal, no proxy)
+        let mut joined = Vec::<serde_json::Value>::with_capacity(plan.endpoints.len());
+
+        for ep in &plan.endpoints {
+            tracer.record("fetch_progress", serde_json::json!({ "endpoint": ep, "status": "start" }));
+            yield ChatEvent::FetchProgress { endpoint: ep.clone(), status: "start" };
+
+            let res = fetch_one(&client_in, &base_url, ep, &plan.params, user_role.as_deref()).await;
+
+            let (status, data) = match res {
+                Ok(v) => ("ok", v),
+                Err(e) => ("error", serde_json::json!({ "error": e.to_string() })),
+            };
+
+            tracer.record("fetch_progress", serde_json::json!({ "endpoint": ep, "status": status }));
+            yield ChatEvent::FetchProgress { endpoint: ep.clone(), status };
+
+            chat_store::save_api_result(&pool, chat_log_id, ep, &plan.params, &data).await;
+            joined.push(serde_json::json!({ "endpoint": ep, "data": data }));
+        }
+
+        let joined_json = serde_json::json!({ "results": joined });
+        let joined_pretty = serde_json::to_string_pretty(&joined_json).unwrap_or_else(|_| "{}".into());
+
+        // joined
+        tracer.record("joined", joined_json.clone());
+        yield ChatEvent::Joined(joined_json.clone());
+
+        // short-circuit if all endpoints errored
+        let all_err = joined_json["results"].as_array().map(|arr| arr.iter().all(|it| it["data"].get("error").is_some())).unwrap_or(false);
+        if all_err {
+            let first_err = joined_json["results"][0]["data"]["error"].as_str().unwrap_or("unknown error");
+            let hint = "Hint: ensure params.service is set (e.g. payments) and backend can reach /api/runtime-logs.";
+            let msg = format!("(fetch error) {first_err}\n{hint}");
+            chat_store::finish(&pool, chat_log_id, &req_id, &msg).await;
+            tracer.record("done", serde_json::json!({ "outcome": "fetch_error", "answer": msg }));
+            yield ChatEvent::Token(msg);
+            yield ChatEvent::Done;
+            return;
+        }
+
+        // response prompt (bukan prompt router) + variabel konteks
+        let vars = AnswerVars {
+            service: plan.params.get("service").cloned(),
+            date_from: plan.params.get("date_from").cloned(),
+            date_to: plan.params.get("date_to").cloned(),
+            tz: plan.params.get("tz").cloned(),
+            endpoints: plan.endpoints.clone(),
+            user_role,
+        };
+        let answer_prompt = prompts::render_answer_prompt(&cfg.response_prompt, &vars);
+
+        // llm_start(answer)
+        tracer.record("llm_start", serde_json::json!({ "step": "answer", "model": model, "system_prompt": answer_prompt }));
+        yield ChatEvent::LlmStart("answer");
+
+        // Final stream call (upstream), history sebagai pasangan user/assistant
+        let mut messages = vec![serde_json::json!({ "role": "system", "content": answer_prompt })];
+        for t in &history {
+            messages.push(serde_json::json!({ "role": "user", "content": t.user_query }));
+            messages.push(serde_json::json!({ "role": "assistant", "content": t.answer_summary() }));
+        }
+        messages.push(serde_json::json!({
+            "role": "user",
+            "content": format!("Question: {}\n\nJoined data:\n{}", user_text, joined_pretty)
+        }));
+        // structured: satu JSON utuh (bukan stream token) yang lalu dipecah jadi event
+        if q.structured {
+            messages.push(serde_json::json!({ "role": "system", "content": answer::instructions(&plan.endpoints) }));
+        }
+        let mut final_prompt = serde_json::json!({ "model": model, "messages": messages });
+        if q.structured {
+            final_prompt["response_format"] = serde_json::json!({ "type": "json_object" });
+        }
+        if stream_answer {
+            final_prompt["stream"] = serde_json::json!(true);
+            final_prompt["stream_options"] = serde_json::json!({ "include_usage": true });
+        }
+        sampling.apply_to(&mut final_prompt);
+
+        let send_res = client_up
+            .post("https://api.openai.com/v1/chat/completions")
+            .bearer_auth(cfg.openai_api_key.clone())
+            .json(&final_prompt)
+            .send()
+            .await;
+
+        // === retry sekali kalau error kirim (transient) ===
+        let send_res = match send_res {
+            Ok(r) => Ok(r),
+            Err(e1) => {
+                tracing::warn!("openai send error (first try): {e1}; retrying once...");
+                tokio::time::sleep(Duration::from_millis(250)).await;
+                client_up
+                    .post("https://api.openai.com/v1/chat/completions")
+                    .bearer_auth(cfg.openai_api_key.clone())
+                    .json(&final_prompt)
+                    .send()
+                    .await
+            }
+        };
+
+        // === Fallback jika pengiriman upstream gagal ===
+        let resp = match send_res {
+            Ok(r) => r,
+            Err(e) => {
+                // 🔁 Local fallback renderer (no LLM)
+                let first = joined_json["results"].get(0).and_then(|x| x.get("data"));
+                let field = |k: &str| first.and_then(|d| d.get(k)).and_then(|s| s.as_str());
+                let header = format!(
+                    "Runtime logs (service={}, tz={}) — checked_at={}\n",
+                    field("service").unwrap_or("unknown"),
+                    field("tz").unwrap_or("UTC"),
+                    field("checked_at").unwrap_or("-"),
+                );
+                let footer = format!("\n(note) LLM formatting skipped: {e}\n");
+
+                let chunks = render_log_fallback(&joined_json, header, footer);
+                chat_store::finish(&pool, chat_log_id, &req_id, &chunks.concat()).await;
+                tracer.record("done", serde_json::json!({ "outcome": "fallback_send_error", "error": e.to_string(), "answer": chunks.concat() }));
+                for chunk in chunks {
+                    yield ChatEvent::Token(chunk);
+                }
+                yield ChatEvent::Done;
+                return;
+            }
+        };
+
+        // === Fallback jika upstream balas non-200 ===
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let s = resp.text().await.unwrap_or_default();
+            #[derive(Deserialize)] struct OaiErr { error: OaiErrInner }
+            #[derive(Deserialize)] struct OaiErrInner { message: String, r#type: String, code: Option<String> }
+            let msg = match serde_json::from_str::<OaiErr>(&s) {
+                Ok(e) => format!("OpenAI error: {} (type={}, code={:?})", e.error.message, e.error.r#type, e.error.code),
+                Err(_) => format!("OpenAI error {status}: {s}"),
+            };
+
+            let chunks = render_log_fallback(
+                &joined_json,
+                "LLM formatting unavailable; showing raw summary:\n".to_string(),
+                format!("\n(note) {msg}\n"),
+            );
+            chat_store::finish(&pool, chat_log_id, &req_id, &chunks.concat()).await;
+            tracer.record("done", serde_json::json!({ "outcome": "fallback_upstream_status", "error": msg, "answer": chunks.concat() }));
+            for chunk in chunks {
+                yield ChatEvent::Token(chunk);
+            }
+            yield ChatEvent::Done;
+            return;
+        }
+
+        if !stream_answer {
+            #[derive(Deserialize)] struct Choice { message: MsgOut }
+            #[derive(Deserialize)] struct MsgOut { content: String }
+            #[derive(Deserialize)] struct AnswerResp { choices: Vec<Choice>, usage: Option<TokenUsage> }
+            let ar: AnswerResp = resp.json().await.map_err(to_axum_error)?;
+            if let Some(u) = &ar.usage {
+                usage::record(&pool, &req_id, q.user_id.as_deref(), &model, "answer", u).await;
+            }
+            let content = ar.choices.first().map(|c| c.message.content.clone()).unwrap_or_default();
+
+            if !q.structured {
+                chat_store::finish(&pool, chat_log_id, &req_id, &content).await;
+                tracer.record("done", serde_json::json!({ "outcome": "ok", "answer": content, "usage": ar.usage }));
+                yield ChatEvent::Token(content);
+                yield ChatEvent::Done;
+                return;
+            }
+            match answer::parse(&content, &plan.endpoints) {
+                Some(sa) => {
+                    let text = sa.render();
+                    chat_store::finish(&pool, chat_log_id, &req_id, &text).await;
+                    tracer.record("done", serde_json::json!({ "outcome": "ok", "structured": sa, "usage": ar.usage }));
+                    yield ChatEvent::Summary { summary: sa.summary, severity: sa.severity };
+                    for (index, finding) in sa.findings.into_iter().enumerate() {
+                        yield ChatEvent::Finding { index, finding };
+                    }
+                    yield ChatEvent::NextActions(sa.next_actions);
+                }
+                None => {
+                    tracing::warn!("structured answer unparseable; sending raw content");
+                    chat_store::finish(&pool, chat_log_id, &req_id, &content).await;
+                    tracer.record("done", serde_json::json!({ "outcome": "structured_parse_error", "answer": content, "usage": ar.usage }));
+                    yield ChatEvent::Token(content);
+                }
+            }
+            yield ChatEvent::Done;
+            return;
+        }
+
+        // stream tokens
+        let mut lines = resp.bytes_stream();
+        let mut buf = Vec::<u8>::new();
+        let mut stream_usage: Option<TokenUsage> = None;
+        let mut answer = String::new();
+
+        while let Some(chunk) = lines.try_next().await.map_err(to_axum_error)? {
+            buf.extend_from_slice(&chunk);
+
+            while let Some(pos) = buf.iter().position(|&b| b == b'\n') {
+                let line_bytes = buf.drain(..=pos).collect::<Vec<u8>>();
+                let line = String::from_utf8_lossy(&line_bytes).trim().to_string();
+                if line.is_empty() { continue; }
+                if !line.starts_with("data:") { continue; }
+
+                let data = line.trim_start_matches("data:").trim().to_string();
+                if data == "[DONE]" { break; }
+
+                #[derive(Deserialize, Debug)] struct StreamDelta { content: Option<String> }
+                #[derive(Deserialize, Debug)] struct StreamChoice { delta: StreamDelta }
+                #[derive(Deserialize, Debug)] struct StreamResp { #[serde(default)] choices: Vec<StreamChoice>, usage: Option<TokenUsage> }
+
+                if let Ok(sr) = serde_json::from_str::<StreamResp>(&data) {
+                    if let Some(choice) = sr.choices.first() {
+                        if let Some(token) = &choice.delta.content {
+                            answer.push_str(token);
+                            yield ChatEvent::Token(token.clone());
+                        }
+                    }
+                    // chunk terakhir (include_usage) membawa usage dengan choices kosong
+                    if sr.usage.is_some() {
+                        stream_usage = sr.usage;
+                    }
+                }
+            }
+        }
+
+        if let Some(u) = &stream_usage {
+            usage::record(&pool, &req_id, q.user_id.as_deref(), &model, "answer", u).await;
+        }
+        chat_store::finish(&pool, chat_log_id, &req_id, &answer).await;
+        tracer.record("done", serde_json::json!({ "outcome": "ok", "answer": answer, "usage": stream_usage }));
+
+        yield ChatEvent::Done;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_clarification_accepted_only_while_pending() {
+        let pending = PendingClarification::default();
+        assert!(!pending.answer("too early".into()));
+
+        let rx = pending.open();
+        assert!(pending.answer("payments".into()));
+        assert!(!pending.answer("duplicate".into()));
+        assert_eq!(rx.await.unwrap(), "payments");
+
+        let _rx = pending.open();
+        pending.close();
+        assert!(!pending.answer("after timeout".into()));
+    }
+
+    #[test]
+    fn test_wire_format_matches_sse_events() {
+        let ev = ChatEvent::FetchProgress {
+            endpoint: "/api/db-perf".into(),
+            status: "ok",
+        };
+        assert_eq!(ev.name(), "fetch_progress");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&ev.data()).unwrap(),
+            serde_json::json!({ "endpoint": "/api/db-perf", "status": "ok" })
+        );
+        assert_eq!(ChatEvent::LlmStart("plan").data(), "plan");
+        assert_eq!(ChatEvent::Done.data(), "done");
+    }
+
+    #[tokio::test]
+    async fn test_collect_reply_concatenates_tokens_only() {
+        let events = futures_util::stream::iter(vec![
+            Ok(ChatEvent::Received("q".into())),
+            Ok(ChatEvent::LlmStart("answer")),
+            Ok(ChatEvent::token("Hello")),
+            Ok(ChatEvent::token(", world")),
+            Ok(ChatEvent::Done),
+        ]);
+        assert_eq!(collect_reply(events).await.unwrap().reply, "Hello, world");
+
+        let failing = futures_util::stream::iter(vec![
+            Ok(ChatEvent::token("partial")),
+            Err(axum::Error::new(std::io::Error::other("boom"))),
+        ]);
+        assert!(collect_reply(failing).await.is_err());
+    }
+}
@@ -0,0 +1,154 @@
+// backend/src/profiles.rs
+
+use sqlx::MySqlPool;
+
+use crate::config::Config;
+use crate::mcp::RouterPlan;
+use crate::models::SettingsProfile;
+
+/// Provider LLM yang sudah punya client di pipeline
+pub const PROVIDERS: &[&str] = &["openai"];
+
+pub(crate) const PROFILE_SELECT: &str = "SELECT id, name, team, provider, model, \
+     CAST(temperature AS DOUBLE) AS temperature, CAST(top_p AS DOUBLE) AS top_p, \
+     system_prompt, response_prompt, allowed_endpoints, created_at, updated_at \
+     FROM settings_profiles";
+
+pub async fn get(pool: &MySqlPool, name: &str) -> Result<Option<SettingsProfile>, sqlx::Error> {
+    sqlx::query_as::<_, SettingsProfile>(&format!("{PROFILE_SELECT} WHERE name = ?"))
+        .bind(name)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Profil untuk request ini: pilihan eksplisit menang, lalu profil default user.
+/// Profil eksplisit yang tidak ada = error; profil default user yang hilang diabaikan.
+pub async fn resolve(
+    pool: &MySqlPool,
+    requested: Option<&str>,
+    user_id: Option<&str>,
+) -> Result<Option<SettingsProfile>, String> {
+    if let Some(name) = requested.filter(|n| !n.trim().is_empty()) {
+        return match get(pool, name).await {
+            Ok(Some(p)) => Ok(Some(p)),
+            Ok(None) => Err(format!("unknown settings profile {name}")),
+            Err(e) => {
+                tracing::warn!(profile = %name, "failed to load settings profile: {e}");
+                Err(format!("settings profile {name} unavailable"))
+            }
+        };
+    }
+
+    let Some(uid) = user_id else { return Ok(None) };
+    let res: Result<Option<SettingsProfile>, _> = sqlx::query_as(&format!(
+        "{PROFILE_SELECT} WHERE name = \
+         (SELECT settings_profile FROM users WHERE username = ? OR email = ? LIMIT 1)"
+    ))
+    .bind(uid)
+    .bind(uid)
+    .fetch_optional(pool)
+    .await;
+    match res {
+        Ok(p) => Ok(p),
+        Err(e) => {
+            tracing::warn!(user_id = %uid, "failed to load user's settings profile: {e}");
+            Ok(None)
+        }
+    }
+}
+
+/// Timpa model & prompt (yang diisi) di Config dengan nilai profil
+pub fn apply(cfg: &mut Config, p: &SettingsProfile) {
+    let set = |dst: &mut String, v: &Option<String>| {
+        if let Some(v) = v.as_ref().filter(|v| !v.trim().is_empty()) {
+            *dst = v.clone();
+        }
+    };
+    set(&mut cfg.model, &p.model);
+    set(&mut cfg.system_prompt, &p.system_prompt);
+    set(&mut cfg.response_prompt, &p.response_prompt);
+}
+
+impl SettingsProfile {
+    pub fn allowed(&self) -> Option<&[String]> {
+        self.allowed_endpoints
+            .as_ref()
+            .map(|j| j.0.as_slice())
+            .filter(|a| !a.is_empty())
+    }
+
+    /// Batasi plan ke subset endpoint profil. Override eksplisit dari client di
+    /// luar subset ditolak; pilihan planner di luar subset dibuang diam-diam.
+    pub fn restrict(&self, plan: &mut RouterPlan, explicit: bool) -> Result<(), String> {
+        let Some(allowed) = self.allowed() else {
+            return Ok(());
+        };
+        if explicit {
+            if let Some(bad) = plan.endpoints.iter().find(|e| !allowed.contains(e)) {
+                return Err(format!(
+                    "endpoint {bad} not allowed by profile {}",
+                    self.name
+                ));
+            }
+            return Ok(());
+        }
+        plan.endpoints.retain(|e| allowed.contains(e));
+        if plan.endpoints.is_empty() {
+            plan.endpoints = allowed.iter().take(3).cloned().collect();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(allowed: Option<Vec<&str>>) -> SettingsProfile {
+        SettingsProfile {
+            id: 1,
+            name: "db-team".into(),
+            team: Some("db".into()),
+            provider: "openai".into(),
+            model: Some("gpt-4o".into()),
+            temperature: None,
+            top_p: None,
+            system_prompt: None,
+            response_prompt: Some("Terse, SQL-focused.".into()),
+            allowed_endpoints: allowed
+                .map(|a| sqlx::types::Json(a.into_iter().map(String::from).collect())),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_restrict_filters_planner_choice_and_rejects_explicit() {
+        let p = profile(Some(vec!["/api/db-perf", "/api/observability"]));
+
+        let mut plan = RouterPlan::new("x", vec!["/api/runtime-logs", "/api/db-perf"]);
+        p.restrict(&mut plan, false).unwrap();
+        assert_eq!(plan.endpoints, vec!["/api/db-perf"]);
+
+        let mut plan = RouterPlan::new("x", vec!["/api/gitlab-ci"]);
+        p.restrict(&mut plan, false).unwrap();
+        assert_eq!(plan.endpoints, vec!["/api/db-perf", "/api/observability"]);
+
+        let mut plan = RouterPlan::new("x", vec!["/api/gitlab-ci"]);
+        assert!(p.restrict(&mut plan, true).is_err());
+
+        let mut plan = RouterPlan::new("x", vec!["/api/gitlab-ci"]);
+        profile(None).restrict(&mut plan, true).unwrap();
+        assert_eq!(plan.endpoints, vec!["/api/gitlab-ci"]);
+    }
+
+    #[test]
+    fn test_apply_only_overrides_filled_fields() {
+        let mut cfg = Config::default();
+        let router_prompt = cfg.system_prompt.clone();
+        apply(&mut cfg, &profile(None));
+        assert_eq!(cfg.model, "gpt-4o");
+        assert_eq!(cfg.system_prompt, router_prompt);
+        assert_eq!(cfg.response_prompt, "Terse, SQL-focused.");
+    }
+}
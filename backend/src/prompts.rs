@@ -0,0 +1,125 @@
+// backend/src/prompts.rs
+
+use sqlx::MySqlPool;
+
+/// Variabel template untuk response prompt (langkah answer).
+/// Placeholder: {{service}} {{date_from}} {{date_to}} {{tz}} {{endpoints}} {{user_role}}
+#[derive(Debug, Clone, Default)]
+pub struct AnswerVars {
+    pub service: Option<String>,
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+    pub tz: Option<String>,
+    pub endpoints: Vec<String>,
+    pub user_role: Option<String>,
+}
+
+impl AnswerVars {
+    fn get(&self, name: &str) -> Option<String> {
+        let v = match name {
+            "service" => self.service.clone(),
+            "date_from" => self.date_from.clone(),
+            "date_to" => self.date_to.clone(),
+            "tz" => self.tz.clone(),
+            "endpoints" => Some(self.endpoints.join(", ")).filter(|s| !s.is_empty()),
+            "user_role" => self.user_role.clone(),
+            _ => return None,
+        };
+        Some(v.unwrap_or_else(|| "unspecified".into()))
+    }
+}
+
+/// Isi placeholder `{{name}}`; placeholder yang tidak dikenal dibiarkan apa adanya.
+///
+/// Prompt lama tanpa placeholder sama sekali tetap mendapat konteks: satu baris
+/// ringkasan variabel ditambahkan di akhir.
+pub fn render_answer_prompt(template: &str, vars: &AnswerVars) -> String {
+    if !template.contains("{{") {
+        return format!(
+            "{}\n\nContext: service={}, date range={} .. {} ({}), endpoints used={}, user role={}.",
+            template.trim_end(),
+            vars.get("service").unwrap_or_default(),
+            vars.get("date_from").unwrap_or_default(),
+            vars.get("date_to").unwrap_or_default(),
+            vars.get("tz").unwrap_or_default(),
+            vars.get("endpoints").unwrap_or_default(),
+            vars.get("user_role").unwrap_or_default(),
+        );
+    }
+
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => {
+                let name = after[..end].trim();
+                match vars.get(name) {
+                    Some(v) => out.push_str(&v),
+                    None => out.push_str(&rest[start..start + 2 + end + 2]),
+                }
+                rest = &after[end + 2..];
+            }
+            None => {
+                out.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Role user (users.role) dari username/email; None kalau tidak ketemu
+pub async fn user_role(pool: &MySqlPool, user_id: &str) -> Option<String> {
+    let res: Result<Option<(String,)>, _> = sqlx::query_as(
+        "SELECT CAST(role AS CHAR) FROM users WHERE username = ? OR email = ? LIMIT 1",
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await;
+    match res {
+        Ok(row) => row.map(|(r,)| r),
+        Err(e) => {
+            tracing::warn!(%user_id, "failed to look up user role: {e}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> AnswerVars {
+        AnswerVars {
+            service: Some("payments".into()),
+            date_from: Some("2025-09-01".into()),
+            date_to: None,
+            tz: Some("Asia/Singapore".into()),
+            endpoints: vec!["/api/runtime-logs".into(), "/api/observability".into()],
+            user_role: Some("viewer".into()),
+        }
+    }
+
+    #[test]
+    fn test_render_fills_known_placeholders() {
+        let out = render_answer_prompt(
+            "Explain {{ service }} for a {{user_role}} from {{date_from}} to {{date_to}} using {{endpoints}}. {{unknown}} {{",
+            &vars(),
+        );
+        assert_eq!(
+            out,
+            "Explain payments for a viewer from 2025-09-01 to unspecified using /api/runtime-logs, /api/observability. {{unknown}} {{"
+        );
+    }
+
+    #[test]
+    fn test_plain_prompt_gets_context_line() {
+        let out = render_answer_prompt("Be concise.", &vars());
+        assert!(out.starts_with("Be concise.\n\nContext: service=payments"));
+        assert!(out.contains("user role=viewer"));
+    }
+}
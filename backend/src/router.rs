@@ -1,15 +1,25 @@
 // backend/src/router.rs
 
 use axum::{
-    extract::Query,
-    routing::{get, post},
+    extract::{FromRef, Query},
+    routing::{get, post, put},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
 use sqlx::MySqlPool;
 
+use crate::adapters::Registry;
+use crate::config::SharedConfig;
 use crate::handlers;
 
+/// State axum; handler cukup mengekstrak bagian yang dipakai (`State<MySqlPool>` dst.)
+#[derive(Clone, FromRef)]
+pub struct AppState {
+    pub pool: MySqlPool,
+    pub config: SharedConfig,
+    pub adapters: Registry,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct Range {
     pub date_from: Option<String>,
@@ -25,8 +35,9 @@ pub struct JoinResult {
     data: serde_json::Value,
 }
 
-pub fn app_routes(pool: MySqlPool) -> Router {
+pub fn app_routes(state: AppState) -> Router {
     Router::new()
+        .route("/api/adapters", get(handlers::adapters::list_adapters))
         .route("/api/gitlab-ci", get(handlers::gitlab_ci::get_gitlab_ci))
         .route(
             "/api/runtime-logs",
@@ -63,13 +74,52 @@ pub fn app_routes(pool: MySqlPool) -> Router {
             "/api/settings",
             get(handlers::settings::get_settings).post(handlers::settings::update_settings),
         )
+        .route(
+            "/api/settings/history",
+            get(handlers::settings::settings_history),
+        )
+        .route(
+            "/api/settings/rollback/:version",
+            post(handlers::settings::rollback_settings),
+        )
+        .route(
+            "/api/profiles",
+            get(handlers::profiles::list_profiles).post(handlers::profiles::create_profile),
+        )
+        .route(
+            "/api/profiles/:name",
+            get(handlers::profiles::get_profile)
+                .put(handlers::profiles::update_profile)
+                .delete(handlers::profiles::delete_profile),
+        )
+        .route(
+            "/api/users/:user_id/profile",
+            put(handlers::profiles::set_user_profile),
+        )
+        .route("/api/usage", get(handlers::usage::get_usage))
+        .route("/api/chats", get(handlers::chat_logs::list_chats))
+        .route("/api/chats/:id", get(handlers::chat_logs::get_chat))
+        .route("/api/traces/:trace_id", get(handlers::traces::get_trace))
+        .route("/api/sessions", get(handlers::sessions::list_sessions))
+        .route(
+            "/api/sessions/:id",
+            get(handlers::sessions::get_session).delete(handlers::sessions::delete_session),
+        )
         .route("/api/chat", post(handlers::chat::chat_handler))
-        .route("/api/chat/stream", get(handlers::chat::chat_stream_handler))
+        .route(
+            "/api/chat/stream",
+            get(handlers::chat::chat_stream_handler).post(handlers::chat::chat_stream_post_handler),
+        )
+        .route("/api/chat/ws", get(handlers::chat_ws::chat_ws_handler))
+        .route(
+            "/api/chat/:stream_id/cancel",
+            post(handlers::chat::cancel_handler),
+        )
         .route(
             "/internal/openai/ping",
             get(handlers::chat::openai_ping_handler),
         )
-        .with_state(pool)
+        .with_state(state)
 }
 
 async fn test_join(Query(q): Query<Range>) -> Json<serde_json::Value> {
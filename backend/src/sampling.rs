@@ -0,0 +1,270 @@
+// backend/src/sampling.rs
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Settings, SettingsProfile};
+
+/// Batas override per-request yang ditetapkan admin (kolom settings.bounds)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SamplingBounds {
+    pub temperature_min: f64,
+    pub temperature_max: f64,
+    pub top_p_min: f64,
+    pub top_p_max: f64,
+    pub max_tokens_limit: u32,
+    pub max_stop_sequences: usize,
+}
+
+impl Default for SamplingBounds {
+    fn default() -> Self {
+        Self {
+            temperature_min: 0.0,
+            temperature_max: 1.0,
+            top_p_min: 0.0,
+            top_p_max: 1.0,
+            max_tokens_limit: 4096,
+            // batas API OpenAI
+            max_stop_sequences: 4,
+        }
+    }
+}
+
+/// Override dari request (semua opsional)
+#[derive(Debug, Clone, Default)]
+pub struct SamplingOverrides {
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    pub max_tokens: Option<u32>,
+    pub stop: Option<Vec<String>>,
+}
+
+/// Parameter sampling final untuk langkah answer
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Sampling {
+    pub temperature: f64,
+    pub top_p: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+}
+
+impl Sampling {
+    /// settings aktif → profil → override request (divalidasi terhadap bounds)
+    pub fn resolve(
+        settings: &Settings,
+        profile: Option<&SettingsProfile>,
+        ov: &SamplingOverrides,
+    ) -> Result<Self, String> {
+        let bounds = settings
+            .bounds
+            .as_ref()
+            .map(|b| b.0.clone())
+            .unwrap_or_default();
+
+        let mut s = Sampling {
+            temperature: settings.temperature,
+            top_p: settings.top_p,
+            max_tokens: settings
+                .max_tokens
+                .and_then(|m| u32::try_from(m).ok())
+                .filter(|m| *m > 0),
+            stop: settings
+                .stop
+                .as_ref()
+                .map(|j| j.0.clone())
+                .unwrap_or_default(),
+        };
+        // profil tetap dijepit ke bounds admin (bounds bisa diperketat setelah profil dibuat)
+        if let Some(p) = profile {
+            if let Some(t) = p.temperature {
+                s.temperature = t.min(bounds.temperature_max).max(bounds.temperature_min);
+            }
+            if let Some(tp) = p.top_p {
+                s.top_p = tp.min(bounds.top_p_max).max(bounds.top_p_min);
+            }
+        }
+
+        if let Some(t) = ov.temperature {
+            let t = t as f64;
+            if !(bounds.temperature_min..=bounds.temperature_max).contains(&t) {
+                return Err(format!(
+                    "temperature {t} outside allowed range {}..={}",
+                    bounds.temperature_min, bounds.temperature_max
+                ));
+            }
+            s.temperature = t;
+        }
+        if let Some(p) = ov.top_p {
+            let p = p as f64;
+            if !(bounds.top_p_min..=bounds.top_p_max).contains(&p) {
+                return Err(format!(
+                    "top_p {p} outside allowed range {}..={}",
+                    bounds.top_p_min, bounds.top_p_max
+                ));
+            }
+            s.top_p = p;
+        }
+        if let Some(m) = ov.max_tokens {
+            if m == 0 || m > bounds.max_tokens_limit {
+                return Err(format!(
+                    "max_tokens must be within 1..={}",
+                    bounds.max_tokens_limit
+                ));
+            }
+            s.max_tokens = Some(m);
+        }
+        if let Some(stop) = &ov.stop {
+            if stop.len() > bounds.max_stop_sequences || stop.iter().any(|x| x.is_empty()) {
+                return Err(format!(
+                    "stop accepts at most {} non-empty sequences",
+                    bounds.max_stop_sequences
+                ));
+            }
+            s.stop = stop.clone();
+        }
+        Ok(s)
+    }
+
+    /// Temperature planner dari settings, dijaga di dalam bounds admin
+    pub fn planner_temperature(settings: &Settings) -> f64 {
+        let bounds = settings
+            .bounds
+            .as_ref()
+            .map(|b| b.0.clone())
+            .unwrap_or_default();
+        settings
+            .planner_temperature
+            .min(bounds.temperature_max)
+            .max(bounds.temperature_min)
+    }
+
+    /// Tambahkan field sampling ke body chat completions
+    pub fn apply_to(&self, body: &mut serde_json::Value) {
+        body["temperature"] = serde_json::json!(self.temperature);
+        body["top_p"] = serde_json::json!(self.top_p);
+        if let Some(m) = self.max_tokens {
+            body["max_tokens"] = serde_json::json!(m);
+        }
+        if !self.stop.is_empty() {
+            body["stop"] = serde_json::json!(self.stop);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> Settings {
+        Settings {
+            system_prompt: "route".into(),
+            response_prompt: None,
+            model: "gpt-4o-mini".into(),
+            temperature: 0.2,
+            top_p: 0.9,
+            planner_temperature: 0.0,
+            streaming: true,
+            max_tokens: Some(800),
+            stop: None,
+            bounds: Some(sqlx::types::Json(SamplingBounds {
+                temperature_max: 0.7,
+                max_tokens_limit: 1000,
+                ..Default::default()
+            })),
+            version: 3,
+            updated_by: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_resolve_uses_settings_then_overrides_within_bounds() {
+        let s = Sampling::resolve(&settings(), None, &SamplingOverrides::default()).unwrap();
+        assert_eq!(
+            (s.temperature, s.top_p, s.max_tokens),
+            (0.2, 0.9, Some(800))
+        );
+
+        let ov = SamplingOverrides {
+            temperature: Some(0.5),
+            max_tokens: Some(200),
+            stop: Some(vec!["\n\n".into()]),
+            ..Default::default()
+        };
+        let s = Sampling::resolve(&settings(), None, &ov).unwrap();
+        assert_eq!(s.temperature, 0.5);
+        assert_eq!(s.max_tokens, Some(200));
+
+        let mut body = serde_json::json!({ "model": "m" });
+        s.apply_to(&mut body);
+        assert_eq!(body["stop"], serde_json::json!(["\n\n"]));
+        assert_eq!(body["max_tokens"], 200);
+    }
+
+    #[test]
+    fn test_resolve_rejects_out_of_bounds_overrides() {
+        let too_hot = SamplingOverrides {
+            temperature: Some(0.9),
+            ..Default::default()
+        };
+        assert!(Sampling::resolve(&settings(), None, &too_hot).is_err());
+
+        let too_long = SamplingOverrides {
+            max_tokens: Some(5000),
+            ..Default::default()
+        };
+        assert!(Sampling::resolve(&settings(), None, &too_long).is_err());
+
+        let many_stops = SamplingOverrides {
+            stop: Some(vec!["a".into(); 5]),
+            ..Default::default()
+        };
+        assert!(Sampling::resolve(&settings(), None, &many_stops).is_err());
+    }
+
+    #[test]
+    fn test_profile_sampling_clamped_to_bounds() {
+        let mut settings = settings();
+        if let Some(b) = settings.bounds.as_mut() {
+            b.0.top_p_max = 0.8;
+        }
+        let profile = SettingsProfile {
+            id: 1,
+            name: "creative".into(),
+            team: None,
+            provider: "openai".into(),
+            model: None,
+            temperature: Some(1.5),
+            top_p: Some(0.95),
+            system_prompt: None,
+            response_prompt: None,
+            allowed_endpoints: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+        let s =
+            Sampling::resolve(&settings, Some(&profile), &SamplingOverrides::default()).unwrap();
+        assert_eq!((s.temperature, s.top_p), (0.7, 0.8));
+
+        let within = SettingsProfile {
+            temperature: Some(0.4),
+            top_p: None,
+            ..profile
+        };
+        let s = Sampling::resolve(&settings, Some(&within), &SamplingOverrides::default()).unwrap();
+        assert_eq!((s.temperature, s.top_p), (0.4, 0.9));
+    }
+
+    #[test]
+    fn test_planner_temperature_from_settings_within_bounds() {
+        let mut s = settings();
+        assert_eq!(Sampling::planner_temperature(&s), 0.0);
+        s.planner_temperature = 0.3;
+        assert_eq!(Sampling::planner_temperature(&s), 0.3);
+        // baris lama di luar bounds tetap dijepit ke batas admin
+        s.planner_temperature = 1.2;
+        assert_eq!(Sampling::planner_temperature(&s), 0.7);
+    }
+}
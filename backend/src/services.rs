@@ -0,0 +1,98 @@
+// backend/src/services.rs
+//
+// Katalog service: nama kanonik + alias yang dipakai user, container, dan label
+// yang dipakai backend (Loki selector, dst.). Diisi dari [services.<nama>] di config.
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServiceEntry {
+    pub aliases: Vec<String>,
+    pub container: Option<String>,
+    /// Label stream/series (mis. app="payments-service", namespace="prod")
+    pub labels: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(transparent)]
+pub struct ServiceCatalog(pub BTreeMap<String, ServiceEntry>);
+
+impl Default for ServiceCatalog {
+    /// Service yang sudah dikenal planner (lihat mcp::infer_service)
+    fn default() -> Self {
+        let entry = |aliases: &[&str], container: &str| ServiceEntry {
+            aliases: aliases.iter().map(|a| a.to_string()).collect(),
+            container: Some(container.to_string()),
+            labels: BTreeMap::from([("app".to_string(), container.to_string())]),
+        };
+        Self(BTreeMap::from([
+            ("payments".into(), entry(&["payment"], "payments-service")),
+            ("auth-service".into(), entry(&["auth"], "auth-service")),
+            ("orders".into(), entry(&["order"], "orders-service")),
+        ]))
+    }
+}
+
+fn valid_label_name(k: &str) -> bool {
+    let mut chars = k.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl ServiceCatalog {
+    /// Cari berdasarkan nama kanonik atau alias (case-insensitive)
+    pub fn lookup(&self, name: &str) -> Option<(&str, &ServiceEntry)> {
+        let name = name.trim().to_lowercase();
+        self.0
+            .iter()
+            .find(|(k, e)| {
+                k.to_lowercase() == name || e.aliases.iter().any(|a| a.to_lowercase() == name)
+            })
+            .map(|(k, e)| (k.as_str(), e))
+    }
+
+    /// Nama container; service di luar katalog → "<service>-service"
+    pub fn container(&self, service: &str) -> String {
+        match self.lookup(service) {
+            Some((_, e)) if e.container.is_some() => e.container.clone().unwrap_or_default(),
+            Some((name, _)) => format!("{name}-service"),
+            None if service == "unknown" => "unknown-service".to_string(),
+            None => format!("{service}-service"),
+        }
+    }
+
+    /// Label selector untuk service; di luar katalog (atau tanpa label) memakai
+    /// `fallback_label="<service>"`
+    pub fn labels(&self, service: &str, fallback_label: &str) -> BTreeMap<String, String> {
+        match self.lookup(service) {
+            Some((_, e)) if !e.labels.is_empty() => e.labels.clone(),
+            Some((name, _)) => BTreeMap::from([(fallback_label.to_string(), name.to_string())]),
+            None => BTreeMap::from([(fallback_label.to_string(), service.to_string())]),
+        }
+    }
+
+    pub fn validate(&self, errs: &mut Vec<String>) {
+        for (name, e) in &self.0 {
+            if let Some(bad) = e.labels.keys().find(|k| !valid_label_name(k)) {
+                errs.push(format!("services.{name}: invalid label name {bad:?}"));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_by_alias_and_fallbacks() {
+        let c = ServiceCatalog::default();
+        assert_eq!(c.lookup("Payment").map(|(n, _)| n), Some("payments"));
+        assert_eq!(c.container("auth"), "auth-service");
+        assert_eq!(c.container("billing"), "billing-service");
+        assert_eq!(c.labels("order", "service")["app"], "orders-service");
+        assert_eq!(c.labels("billing", "service")["service"], "billing");
+    }
+}
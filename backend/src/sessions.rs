@@ -0,0 +1,170 @@
+// backend/src/sessions.rs
+
+use serde::Serialize;
+use sqlx::MySqlPool;
+
+/// Panjang ringkasan jawaban sebelumnya yang dibawa ke prompt berikutnya
+const ANSWER_SUMMARY_CHARS: usize = 600;
+
+/// Satu giliran percakapan sebelumnya (diambil dari chat_logs)
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Turn {
+    pub user_query: String,
+    pub detected_intent: Option<String>,
+    pub routed_endpoints: Option<serde_json::Value>,
+    pub response_summary: Option<String>,
+}
+
+impl Turn {
+    pub fn answer_summary(&self) -> String {
+        let a = self.response_summary.as_deref().unwrap_or("").trim();
+        if a.chars().count() <= ANSWER_SUMMARY_CHARS {
+            return a.to_string();
+        }
+        let cut: String = a.chars().take(ANSWER_SUMMARY_CHARS).collect();
+        format!("{cut}…")
+    }
+}
+
+/// id dibuat client (mis. UUID); batasi ke karakter aman & panjang kolom
+pub fn valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 64
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Perkiraan kasar (≈4 karakter per token) — cukup untuk memangkas history
+pub fn estimate_tokens(s: &str) -> usize {
+    s.chars().count().div_ceil(4)
+}
+
+fn turn_tokens(t: &Turn) -> usize {
+    estimate_tokens(&t.user_query) + estimate_tokens(&t.answer_summary()) + 16
+}
+
+/// Simpan giliran terbaru sebanyak yang muat di budget (urutan tetap lama → baru)
+pub fn trim_to_budget(turns: Vec<Turn>, budget_tokens: usize, max_turns: usize) -> Vec<Turn> {
+    let mut used = 0usize;
+    let mut keep = 0usize;
+    for t in turns.iter().rev().take(max_turns) {
+        let cost = turn_tokens(t);
+        if used + cost > budget_tokens {
+            break;
+        }
+        used += cost;
+        keep += 1;
+    }
+    let skip = turns.len() - keep;
+    turns.into_iter().skip(skip).collect()
+}
+
+/// Ringkasan history untuk prompt planner
+pub fn planner_context(turns: &[Turn]) -> String {
+    let mut out = String::new();
+    for (i, t) in turns.iter().enumerate() {
+        out.push_str(&format!(
+            "[{}] User: {}\n    Plan: intent={} endpoints={}\n    Answer: {}\n",
+            i + 1,
+            t.user_query,
+            t.detected_intent.as_deref().unwrap_or("-"),
+            t.routed_endpoints
+                .as_ref()
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "[]".into()),
+            t.answer_summary(),
+        ));
+    }
+    out
+}
+
+/// Buat session kalau belum ada (best-effort)
+pub async fn ensure(pool: &MySqlPool, session_id: &str, user_id: Option<&str>, first_query: &str) {
+    let title: String = first_query.chars().take(120).collect();
+    let res = sqlx::query(
+        "INSERT INTO chat_sessions (id, user_id, title) VALUES (?, ?, ?)
+         ON DUPLICATE KEY UPDATE updated_at = CURRENT_TIMESTAMP",
+    )
+    .bind(session_id)
+    .bind(user_id)
+    .bind(title)
+    .execute(pool)
+    .await;
+    if let Err(e) = res {
+        tracing::warn!(%session_id, "failed to upsert chat_session: {e}");
+    }
+}
+
+/// History session (lama → baru), sudah dipangkas ke budget token
+pub async fn load_history(
+    pool: &MySqlPool,
+    session_id: &str,
+    budget_tokens: usize,
+    max_turns: usize,
+) -> Vec<Turn> {
+    let res: Result<Vec<Turn>, _> = sqlx::query_as(
+        "SELECT user_query, detected_intent, routed_endpoints, response_summary
+         FROM chat_logs WHERE session_id = ? ORDER BY id DESC LIMIT ?",
+    )
+    .bind(session_id)
+    .bind(max_turns as u32)
+    .fetch_all(pool)
+    .await;
+
+    match res {
+        Ok(mut turns) => {
+            turns.reverse();
+            trim_to_budget(turns, budget_tokens, max_turns)
+        }
+        Err(e) => {
+            tracing::warn!(%session_id, "failed to load session history: {e}");
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(q: &str, a: &str) -> Turn {
+        Turn {
+            user_query: q.into(),
+            detected_intent: Some("logs_fetch".into()),
+            routed_endpoints: Some(serde_json::json!(["/api/runtime-logs"])),
+            response_summary: Some(a.into()),
+        }
+    }
+
+    #[test]
+    fn test_valid_id() {
+        assert!(valid_id("3f2b6c1e-8a4d-4d0b-9a57-1f9a2d7c0e11"));
+        assert!(!valid_id(""));
+        assert!(!valid_id("abc; DROP TABLE"));
+        assert!(!valid_id(&"a".repeat(65)));
+    }
+
+    #[test]
+    fn test_trim_keeps_newest_turns_within_budget() {
+        let turns = vec![
+            turn("first question", &"x".repeat(400)),
+            turn("second question", &"y".repeat(400)),
+            turn("and what about auth?", "short"),
+        ];
+        // budget cukup untuk 2 giliran terakhir saja
+        let kept = trim_to_budget(turns.clone(), 150, 10);
+        let qs: Vec<_> = kept.iter().map(|t| t.user_query.as_str()).collect();
+        assert_eq!(qs, vec!["second question", "and what about auth?"]);
+
+        let kept = trim_to_budget(turns, 10_000, 1);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].user_query, "and what about auth?");
+    }
+
+    #[test]
+    fn test_answer_summary_is_capped() {
+        let t = turn("q", &"z".repeat(2000));
+        assert_eq!(t.answer_summary().chars().count(), ANSWER_SUMMARY_CHARS + 1);
+    }
+}
@@ -0,0 +1,318 @@
+// backend/src/settings_store.rs
+
+use sqlx::MySqlPool;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use crate::config::Config;
+use crate::models::{Settings, SettingsRevision};
+use crate::sampling::SamplingBounds;
+
+const SETTINGS_SELECT: &str = "SELECT system_prompt, response_prompt, model, \
+     CAST(temperature AS DOUBLE) AS temperature, CAST(top_p AS DOUBLE) AS top_p, \
+     CAST(planner_temperature AS DOUBLE) AS planner_temperature, \
+     max_tokens, stop, bounds, streaming, version, updated_by, updated_at FROM settings WHERE id = 1";
+
+const REVISION_SELECT: &str = "SELECT id, version, system_prompt, response_prompt, model, \
+     CAST(temperature AS DOUBLE) AS temperature, CAST(top_p AS DOUBLE) AS top_p, \
+     CAST(planner_temperature AS DOUBLE) AS planner_temperature, \
+     max_tokens, stop, bounds, streaming, author, note, created_at FROM settings_revisions";
+
+/// Isi settings baru (payload POST /api/settings atau snapshot revisi untuk rollback)
+#[derive(Debug, Clone)]
+pub struct NewSettings {
+    pub system_prompt: String,
+    pub response_prompt: Option<String>,
+    pub model: String,
+    pub temperature: f64,
+    pub top_p: f64,
+    pub planner_temperature: f64,
+    pub max_tokens: Option<u32>,
+    pub stop: Option<Vec<String>>,
+    pub bounds: Option<SamplingBounds>,
+    pub streaming: bool,
+}
+
+/* ------------------------- Cache ------------------------- */
+
+/// Settings ter-cache + generasi; generasi naik di setiap invalidate supaya
+/// pembaca yang mulai sebelum save tidak mengisi ulang cache dengan versi lama
+#[derive(Default)]
+struct Cache {
+    generation: u64,
+    value: Option<Arc<Settings>>,
+}
+
+impl Cache {
+    fn invalidate(&mut self) {
+        self.generation += 1;
+        self.value = None;
+    }
+
+    /// Isi cache hanya bila belum ada invalidate sejak `generation` dibaca
+    fn fill(&mut self, generation: u64, s: Arc<Settings>) {
+        if self.generation == generation {
+            self.value = Some(s);
+        }
+    }
+}
+
+fn cache() -> &'static RwLock<Cache> {
+    static CACHE: OnceLock<RwLock<Cache>> = OnceLock::new();
+    CACHE.get_or_init(|| RwLock::new(Cache::default()))
+}
+
+pub fn invalidate() {
+    cache()
+        .write()
+        .expect("settings cache poisoned")
+        .invalidate();
+}
+
+/// Nilai dari env, dipakai sebelum ada baris settings / saat DB tidak bisa dibaca
+pub fn defaults(cfg: &Config) -> Settings {
+    Settings {
+        system_prompt: cfg.system_prompt.clone(),
+        response_prompt: Some(cfg.response_prompt.clone()),
+        model: cfg.model.clone(),
+        temperature: 0.2,
+        top_p: 0.9,
+        planner_temperature: 0.0,
+        max_tokens: None,
+        stop: None,
+        bounds: None,
+        streaming: true,
+        version: 0,
+        updated_by: None,
+        updated_at: None,
+    }
+}
+
+/// Settings aktif (cache in-memory; diisi ulang setelah [`invalidate`])
+pub async fn active(pool: &MySqlPool, cfg: &Config) -> Arc<Settings> {
+    let generation = {
+        let c = cache().read().expect("settings cache poisoned");
+        if let Some(s) = &c.value {
+            return s.clone();
+        }
+        c.generation
+    };
+    match sqlx::query_as::<_, Settings>(SETTINGS_SELECT)
+        .fetch_optional(pool)
+        .await
+    {
+        Ok(row) => {
+            let s = Arc::new(row.unwrap_or_else(|| defaults(cfg)));
+            cache()
+                .write()
+                .expect("settings cache poisoned")
+                .fill(generation, s.clone());
+            s
+        }
+        Err(e) => {
+            // jangan di-cache: request berikutnya coba DB lagi
+            tracing::warn!("failed to load settings; using env defaults: {e}");
+            Arc::new(defaults(cfg))
+        }
+    }
+}
+
+/// Timpa model & prompt di Config dengan settings aktif — hanya bila admin sudah
+/// menyimpan versi (`version > 0`); sebelum itu env/TOML yang berlaku
+pub fn apply(cfg: &mut Config, s: &Settings) {
+    if s.version <= 0 {
+        return;
+    }
+    cfg.model = s.model.clone();
+    cfg.system_prompt = s.system_prompt.clone();
+    if let Some(rp) = s.response_prompt.as_ref().filter(|p| !p.trim().is_empty()) {
+        cfg.response_prompt = rp.clone();
+    }
+}
+
+/* ------------------------- Write ------------------------- */
+
+/// Catat nilai config saat start sebagai revisi v0 (sekali saja) supaya bisa
+/// di-rollback ke kondisi awal; best-effort, error hanya di-log
+pub async fn seed_initial_revision(pool: &MySqlPool, cfg: &Config) {
+    let d = defaults(cfg);
+    let res = sqlx::query(
+        "INSERT IGNORE INTO settings_revisions
+           (version, system_prompt, response_prompt, model, temperature, top_p,
+            planner_temperature, streaming, note)
+         VALUES (0, ?, ?, ?, ?, ?, ?, ?, 'initial (config)')",
+    )
+    .bind(&d.system_prompt)
+    .bind(&d.response_prompt)
+    .bind(&d.model)
+    .bind(d.temperature)
+    .bind(d.top_p)
+    .bind(d.planner_temperature)
+    .bind(d.streaming)
+    .execute(pool)
+    .await;
+    if let Err(e) = res {
+        tracing::warn!("failed to record initial settings revision: {e}");
+    }
+}
+
+/// Simpan settings sebagai versi baru (baris aktif + revisi) dalam satu transaksi
+pub async fn save(
+    pool: &MySqlPool,
+    new: &NewSettings,
+    author: Option<&str>,
+    note: Option<&str>,
+) -> Result<Settings, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    // unique key di version menjaga dua update bersamaan tidak memakai nomor yang sama
+    let (next,): (i64,) = sqlx::query_as(
+        "SELECT CAST(COALESCE(MAX(version), 0) + 1 AS SIGNED) FROM settings_revisions FOR UPDATE",
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    let version = next as i32;
+
+    sqlx::query(
+        "INSERT INTO settings_revisions
+           (version, system_prompt, response_prompt, model, temperature, top_p,
+            planner_temperature, max_tokens, stop, bounds, streaming, author, note)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(version)
+    .bind(&new.system_prompt)
+    .bind(&new.response_prompt)
+    .bind(&new.model)
+    .bind(new.temperature)
+    .bind(new.top_p)
+    .bind(new.planner_temperature)
+    .bind(new.max_tokens)
+    .bind(new.stop.as_ref().map(|s| serde_json::json!(s)))
+    .bind(new.bounds.as_ref().map(|b| serde_json::json!(b)))
+    .bind(new.streaming)
+    .bind(author)
+    .bind(note)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO settings
+           (id, system_prompt, response_prompt, model, temperature, top_p,
+            planner_temperature, max_tokens, stop, bounds, streaming, version, updated_by)
+         VALUES (1, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+         ON DUPLICATE KEY UPDATE
+           system_prompt = VALUES(system_prompt), response_prompt = VALUES(response_prompt),
+           model = VALUES(model), temperature = VALUES(temperature), top_p = VALUES(top_p),
+           planner_temperature = VALUES(planner_temperature),
+           max_tokens = VALUES(max_tokens), stop = VALUES(stop), bounds = VALUES(bounds),
+           streaming = VALUES(streaming), version = VALUES(version), updated_by = VALUES(updated_by)",
+    )
+    .bind(&new.system_prompt)
+    .bind(&new.response_prompt)
+    .bind(&new.model)
+    .bind(new.temperature)
+    .bind(new.top_p)
+    .bind(new.planner_temperature)
+    .bind(new.max_tokens)
+    .bind(new.stop.as_ref().map(|s| serde_json::json!(s)))
+    .bind(new.bounds.as_ref().map(|b| serde_json::json!(b)))
+    .bind(new.streaming)
+    .bind(version)
+    .bind(author)
+    .execute(&mut *tx)
+    .await?;
+
+    let saved = sqlx::query_as::<_, Settings>(SETTINGS_SELECT)
+        .fetch_one(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    invalidate();
+    tracing::info!(version, author = ?author, "⚙️ settings updated");
+    Ok(saved)
+}
+
+/// Aktifkan lagi isi revisi `version` — dicatat sebagai versi baru, history tidak ditulis ulang
+pub async fn rollback(
+    pool: &MySqlPool,
+    version: i32,
+    author: Option<&str>,
+) -> Result<Option<Settings>, sqlx::Error> {
+    let rev =
+        sqlx::query_as::<_, SettingsRevision>(&format!("{REVISION_SELECT} WHERE version = ?"))
+            .bind(version)
+            .fetch_optional(pool)
+            .await?;
+    let Some(rev) = rev else { return Ok(None) };
+
+    let new = NewSettings {
+        system_prompt: rev.system_prompt,
+        response_prompt: rev.response_prompt,
+        model: rev.model,
+        temperature: rev.temperature,
+        top_p: rev.top_p,
+        planner_temperature: rev.planner_temperature,
+        max_tokens: rev.max_tokens.and_then(|m| u32::try_from(m).ok()),
+        stop: rev.stop.map(|j| j.0),
+        bounds: rev.bounds.map(|j| j.0),
+        streaming: rev.streaming,
+    };
+    let note = format!("rollback to v{version}");
+    save(pool, &new, author, Some(&note)).await.map(Some)
+}
+
+/// Riwayat revisi, terbaru dulu
+pub async fn revisions(
+    pool: &MySqlPool,
+    limit: u32,
+    offset: u32,
+) -> Result<Vec<SettingsRevision>, sqlx::Error> {
+    sqlx::query_as::<_, SettingsRevision>(&format!(
+        "{REVISION_SELECT} ORDER BY version DESC LIMIT ? OFFSET ?"
+    ))
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_read_does_not_refill_after_invalidate() {
+        let stale = Arc::new(defaults(&Config::default()));
+        let mut c = Cache::default();
+
+        // pembaca mulai (cache kosong), lalu save() → invalidate sebelum pembaca selesai
+        let read_started = c.generation;
+        c.invalidate();
+        c.fill(read_started, stale.clone());
+        assert!(c.value.is_none());
+
+        c.fill(c.generation, stale);
+        assert!(c.value.is_some());
+    }
+
+    #[test]
+    fn test_apply_keeps_config_until_admin_saves() {
+        let cfg = Config {
+            model: "from-env".into(),
+            system_prompt: "env router".into(),
+            ..Config::default()
+        };
+        let mut row = defaults(&Config::default());
+        row.model = "legacy-row".into();
+        row.system_prompt = "legacy router".into();
+
+        let mut c = cfg.clone();
+        apply(&mut c, &row);
+        assert_eq!(c.model, "from-env");
+        assert_eq!(c.system_prompt, "env router");
+
+        row.version = 1;
+        apply(&mut c, &row);
+        assert_eq!(c.model, "legacy-row");
+        assert_eq!(c.system_prompt, "legacy router");
+    }
+}
@@ -0,0 +1,333 @@
+// backend/src/stream_buffer.rs
+
+use futures_util::Stream;
+use rand::RngCore;
+use std::collections::hash_map::{Entry, HashMap};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// Satu event SSE yang sudah diberi nomor urut (id = "{stream_id}:{seq}")
+#[derive(Debug, Clone, PartialEq)]
+pub struct BufferedEvent {
+    pub seq: u64,
+    pub event: String,
+    pub data: String,
+}
+
+#[derive(Default)]
+struct Inner {
+    events: Vec<BufferedEvent>,
+    finished: bool,
+    cancel_reason: Option<String>,
+}
+
+/// Buffer event untuk satu chat stream. Pipeline menulis ke sini (task
+/// terpisah), koneksi SSE hanya membaca — jadi client yang putus bisa
+/// reconnect dengan `Last-Event-ID` dan melanjutkan tanpa mengulang LLM.
+///
+/// Kalau tidak ada koneksi yang membaca selama `orphan_grace`, stream
+/// dianggap ditinggal client dan dibatalkan (lihat [`StreamBuffer::cancelled`]).
+pub struct StreamBuffer {
+    id: String,
+    /// session_id yang membuka stream; hanya session ini yang boleh cancel
+    owner: Option<String>,
+    inner: Mutex<Inner>,
+    seq_tx: watch::Sender<u64>,
+    cancel_tx: watch::Sender<bool>,
+    subscribers: AtomicUsize,
+    orphan_grace: Duration,
+}
+
+impl StreamBuffer {
+    fn new(id: String, owner: Option<String>, orphan_grace: Duration) -> Self {
+        let (seq_tx, _) = watch::channel(0);
+        let (cancel_tx, _) = watch::channel(false);
+        Self {
+            id,
+            owner,
+            inner: Mutex::new(Inner::default()),
+            seq_tx,
+            cancel_tx,
+            subscribers: AtomicUsize::new(0),
+            orphan_grace,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Stream tanpa session boleh dikontrol siapa pun yang tahu id-nya
+    pub fn owned_by(&self, session_id: Option<&str>) -> bool {
+        self.owner.is_none() || self.owner.as_deref() == session_id
+    }
+
+    pub fn push(&self, event: impl Into<String>, data: impl Into<String>) -> u64 {
+        let mut inner = self.inner.lock().expect("stream buffer poisoned");
+        let seq = inner.events.len() as u64 + 1;
+        inner.events.push(BufferedEvent {
+            seq,
+            event: event.into(),
+            data: data.into(),
+        });
+        drop(inner);
+        self.seq_tx.send_replace(seq);
+        seq
+    }
+
+    pub fn finish(&self) {
+        let mut inner = self.inner.lock().expect("stream buffer poisoned");
+        inner.finished = true;
+        let last = inner.events.len() as u64;
+        drop(inner);
+        self.seq_tx.send_replace(last);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.inner.lock().expect("stream buffer poisoned").finished
+    }
+
+    /// Minta pipeline berhenti. Return false kalau sudah selesai/dibatalkan.
+    pub fn cancel(&self, reason: &str) -> bool {
+        let mut inner = self.inner.lock().expect("stream buffer poisoned");
+        if inner.finished || inner.cancel_reason.is_some() {
+            return false;
+        }
+        inner.cancel_reason = Some(reason.to_string());
+        drop(inner);
+        self.cancel_tx.send_replace(true);
+        true
+    }
+
+    pub fn cancel_reason(&self) -> Option<String> {
+        self.inner
+            .lock()
+            .expect("stream buffer poisoned")
+            .cancel_reason
+            .clone()
+    }
+
+    /// Selesai saat [`StreamBuffer::cancel`] dipanggil
+    pub async fn cancelled(&self) {
+        let mut rx = self.cancel_tx.subscribe();
+        let _ = rx.wait_for(|c| *c).await;
+    }
+
+    fn snapshot_after(&self, after: u64) -> (Vec<BufferedEvent>, bool) {
+        let inner = self.inner.lock().expect("stream buffer poisoned");
+        let pending = inner.events.iter().skip(after as usize).cloned().collect();
+        (pending, inner.finished)
+    }
+
+    /// Replay event dengan seq > `after`, lalu ikuti event baru sampai selesai
+    pub fn subscribe(self: Arc<Self>, after: u64) -> impl Stream<Item = BufferedEvent> {
+        let mut rx = self.seq_tx.subscribe();
+        let guard = SubscriberGuard::new(self.clone());
+        async_stream::stream! {
+            let _guard = guard;
+            let mut next = after;
+            loop {
+                rx.borrow_and_update();
+                let (pending, finished) = self.snapshot_after(next);
+                for ev in pending {
+                    next = ev.seq;
+                    yield ev;
+                }
+                if finished {
+                    break;
+                }
+                if rx.changed().await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Hitung koneksi SSE yang aktif; koneksi terakhir putus → mulai timer orphan
+struct SubscriberGuard {
+    buf: Arc<StreamBuffer>,
+}
+
+impl SubscriberGuard {
+    fn new(buf: Arc<StreamBuffer>) -> Self {
+        buf.subscribers.fetch_add(1, Ordering::SeqCst);
+        Self { buf }
+    }
+}
+
+impl Drop for SubscriberGuard {
+    fn drop(&mut self) {
+        if self.buf.subscribers.fetch_sub(1, Ordering::SeqCst) != 1 || self.buf.is_finished() {
+            return;
+        }
+        let Ok(rt) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let buf = self.buf.clone();
+        rt.spawn(async move {
+            tokio::time::sleep(buf.orphan_grace).await;
+            if buf.subscribers.load(Ordering::SeqCst) == 0 && buf.cancel("client_disconnected") {
+                tracing::info!(stream_id = %buf.id(), "✂️ no client reconnected; cancelling chat stream");
+            }
+        });
+    }
+}
+
+/* ------------------------- Registry ------------------------- */
+
+fn registry() -> &'static Mutex<HashMap<String, Arc<StreamBuffer>>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<String, Arc<StreamBuffer>>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Daftarkan buffer baru; None kalau `id` masih dipakai stream lain (berjalan
+/// atau masih dalam jendela resume) — buffer lama tidak pernah ditimpa
+pub fn register(
+    id: &str,
+    owner: Option<&str>,
+    orphan_grace: Duration,
+) -> Option<Arc<StreamBuffer>> {
+    let mut reg = registry().lock().expect("stream registry poisoned");
+    match reg.entry(id.to_string()) {
+        Entry::Occupied(_) => None,
+        Entry::Vacant(v) => {
+            let buf = Arc::new(StreamBuffer::new(
+                id.to_string(),
+                owner.map(str::to_string),
+                orphan_grace,
+            ));
+            v.insert(buf.clone());
+            Some(buf)
+        }
+    }
+}
+
+/// Id stream acak dari server (bukan x-request-id dari client), sulit ditebak
+fn new_stream_id() -> String {
+    let mut b = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut b);
+    hex::encode(b)
+}
+
+/// Daftarkan buffer dengan id baru dari [`new_stream_id`]
+pub fn register_new(owner: Option<&str>, orphan_grace: Duration) -> Arc<StreamBuffer> {
+    loop {
+        if let Some(buf) = register(&new_stream_id(), owner, orphan_grace) {
+            return buf;
+        }
+    }
+}
+
+pub fn get(id: &str) -> Option<Arc<StreamBuffer>> {
+    registry()
+        .lock()
+        .expect("stream registry poisoned")
+        .get(id)
+        .cloned()
+}
+
+/// Tandai selesai dan hapus dari registry setelah `window` (jendela resume)
+pub fn finish_and_expire(buf: Arc<StreamBuffer>, window: Duration) {
+    buf.finish();
+    tokio::spawn(async move {
+        tokio::time::sleep(window).await;
+        let mut reg = registry().lock().expect("stream registry poisoned");
+        if reg.get(buf.id()).is_some_and(|b| Arc::ptr_eq(b, &buf)) {
+            reg.remove(buf.id());
+        }
+    });
+}
+
+/// "{stream_id}:{seq}" → (stream_id, seq)
+pub fn parse_event_id(id: &str) -> Option<(&str, u64)> {
+    let (stream_id, seq) = id.rsplit_once(':')?;
+    if stream_id.is_empty() {
+        return None;
+    }
+    Some((stream_id, seq.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+
+    #[test]
+    fn test_parse_event_id() {
+        assert_eq!(parse_event_id("abc-123:7"), Some(("abc-123", 7)));
+        assert_eq!(parse_event_id("abc-123"), None);
+        assert_eq!(parse_event_id(":7"), None);
+        assert_eq!(parse_event_id("abc:x"), None);
+    }
+
+    #[tokio::test]
+    async fn test_register_never_replaces_existing_stream() {
+        let buf = register("test-taken", None, Duration::from_secs(60)).unwrap();
+        buf.push("token", "mine");
+        assert!(register("test-taken", None, Duration::from_secs(60)).is_none());
+
+        // selesai tapi masih dalam jendela resume → tetap milik stream lama
+        finish_and_expire(buf.clone(), Duration::from_millis(10));
+        assert!(register("test-taken", None, Duration::from_secs(60)).is_none());
+        assert!(Arc::ptr_eq(&get("test-taken").unwrap(), &buf));
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(register("test-taken", None, Duration::from_secs(60)).is_some());
+
+        let a = register_new(None, Duration::from_secs(60));
+        let b = register_new(None, Duration::from_secs(60));
+        assert_ne!(a.id(), b.id());
+        assert_eq!(a.id().len(), 32);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_replays_after_last_seen_and_follows_live() {
+        let buf = register("test-resume", None, Duration::from_secs(60)).unwrap();
+        buf.push("received", "hi");
+        buf.push("token", "a");
+
+        let reader = tokio::spawn({
+            let buf = buf.clone();
+            async move { buf.subscribe(1).collect::<Vec<_>>().await }
+        });
+
+        tokio::task::yield_now().await;
+        buf.push("token", "b");
+        buf.push("done", "done");
+        finish_and_expire(buf.clone(), Duration::from_millis(10));
+
+        let got = reader.await.unwrap();
+        let seqs: Vec<_> = got.iter().map(|e| (e.seq, e.data.as_str())).collect();
+        assert_eq!(seqs, vec![(2, "a"), (3, "b"), (4, "done")]);
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(get("test-resume").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_orphaned_stream_is_cancelled_after_grace() {
+        let buf = register("test-orphan", None, Duration::from_millis(20)).unwrap();
+        let sub = buf.clone().subscribe(0);
+        drop(sub);
+
+        tokio::time::timeout(Duration::from_secs(1), buf.cancelled())
+            .await
+            .expect("orphan stream should be cancelled");
+        assert_eq!(buf.cancel_reason().as_deref(), Some("client_disconnected"));
+        // sudah dibatalkan → cancel berikutnya no-op
+        assert!(!buf.cancel("client_request"));
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_within_grace_keeps_stream_alive() {
+        let buf = register("test-reconnect", None, Duration::from_millis(50)).unwrap();
+        drop(buf.clone().subscribe(0));
+        let _again = buf.clone().subscribe(0);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(buf.cancel_reason().is_none());
+    }
+}
@@ -0,0 +1,62 @@
+// backend/src/traces.rs
+
+use serde_json::Value;
+use sqlx::MySqlPool;
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+struct TraceRow {
+    phase: String,
+    payload: Value,
+    elapsed_ms: u64,
+}
+
+/// Penulis debug_traces per request (trace_id = x-request-id).
+///
+/// Insert dilakukan oleh satu task background lewat channel, jadi urutan fase
+/// terjaga dan SSE tidak menunggu DB. Kalau recorder di-drop (mis. client
+/// putus), baris yang sudah dikirim tetap ditulis sampai habis.
+#[derive(Clone)]
+pub struct TraceRecorder {
+    tx: mpsc::UnboundedSender<TraceRow>,
+    started: Instant,
+}
+
+impl TraceRecorder {
+    pub fn new(pool: MySqlPool, trace_id: String) -> Self {
+        let (tx, mut rx) = mpsc::unbounded_channel::<TraceRow>();
+
+        tokio::spawn(async move {
+            while let Some(row) = rx.recv().await {
+                let res = sqlx::query(
+                    "INSERT INTO debug_traces (trace_id, phase, payload, elapsed_ms) VALUES (?, ?, ?, ?)",
+                )
+                .bind(&trace_id)
+                .bind(&row.phase)
+                .bind(&row.payload)
+                .bind(row.elapsed_ms)
+                .execute(&pool)
+                .await;
+
+                if let Err(e) = res {
+                    tracing::warn!(%trace_id, phase = %row.phase, "failed to write debug_trace: {e}");
+                }
+            }
+        });
+
+        Self {
+            tx,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn record(&self, phase: &str, payload: Value) {
+        let row = TraceRow {
+            phase: phase.to_string(),
+            payload,
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+        };
+        // writer task hanya berhenti kalau semua sender sudah di-drop
+        let _ = self.tx.send(row);
+    }
+}
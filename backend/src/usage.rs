@@ -0,0 +1,120 @@
+// backend/src/usage.rs
+
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+
+/// Token counts as reported by OpenAI (`usage` on non-stream responses,
+/// last chunk of a stream when `stream_options.include_usage` is set).
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct TokenUsage {
+    #[serde(default)]
+    pub prompt_tokens: u32,
+    #[serde(default)]
+    pub completion_tokens: u32,
+}
+
+/// Harga per 1 juta token (USD)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPrice {
+    pub input_per_1m: f64,
+    pub output_per_1m: f64,
+}
+
+/// (model prefix, input USD / 1M, output USD / 1M)
+const PRICES: &[(&str, f64, f64)] = &[
+    ("gpt-4o-mini", 0.15, 0.60),
+    ("gpt-4o", 2.50, 10.00),
+    ("gpt-4.1-nano", 0.10, 0.40),
+    ("gpt-4.1-mini", 0.40, 1.60),
+    ("gpt-4.1", 2.00, 8.00),
+    ("gpt-3.5-turbo", 0.50, 1.50),
+    ("o3-mini", 1.10, 4.40),
+];
+
+/// Longest-prefix match, so dated snapshots (e.g. `gpt-4o-mini-2024-07-18`)
+/// resolve to their base model.
+pub fn price_for(model: &str) -> Option<ModelPrice> {
+    PRICES
+        .iter()
+        .filter(|(name, _, _)| model.starts_with(name))
+        .max_by_key(|(name, _, _)| name.len())
+        .map(|&(_, input_per_1m, output_per_1m)| ModelPrice {
+            input_per_1m,
+            output_per_1m,
+        })
+}
+
+pub fn cost_usd(model: &str, usage: &TokenUsage) -> f64 {
+    match price_for(model) {
+        Some(p) => {
+            (usage.prompt_tokens as f64 * p.input_per_1m
+                + usage.completion_tokens as f64 * p.output_per_1m)
+                / 1_000_000.0
+        }
+        None => {
+            tracing::warn!(%model, "no price configured for model; cost recorded as 0");
+            0.0
+        }
+    }
+}
+
+/// Simpan pemakaian token satu panggilan LLM (best-effort, error hanya di-log)
+pub async fn record(
+    pool: &MySqlPool,
+    request_id: &str,
+    user_id: Option<&str>,
+    model: &str,
+    phase: &str,
+    usage: &TokenUsage,
+) {
+    let cost = cost_usd(model, usage);
+    tracing::info!(
+        %request_id, %model, %phase,
+        prompt_tokens = usage.prompt_tokens,
+        completion_tokens = usage.completion_tokens,
+        cost_usd = cost,
+        "💰 token usage"
+    );
+
+    let res = sqlx::query(
+        "INSERT INTO llm_usage (request_id, user_id, model, phase, prompt_tokens, completion_tokens, cost_usd)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(request_id)
+    .bind(user_id)
+    .bind(model)
+    .bind(phase)
+    .bind(usage.prompt_tokens)
+    .bind(usage.completion_tokens)
+    .bind(cost)
+    .execute(pool)
+    .await;
+
+    if let Err(e) = res {
+        tracing::warn!(%request_id, "failed to record token usage: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_price_prefers_longest_prefix() {
+        let mini = price_for("gpt-4o-mini-2024-07-18").unwrap();
+        assert_eq!(mini.input_per_1m, 0.15);
+        let full = price_for("gpt-4o-2024-08-06").unwrap();
+        assert_eq!(full.input_per_1m, 2.50);
+        assert!(price_for("llama-3").is_none());
+    }
+
+    #[test]
+    fn test_cost_usd() {
+        let u = TokenUsage {
+            prompt_tokens: 1_000_000,
+            completion_tokens: 500_000,
+        };
+        assert!((cost_usd("gpt-4o-mini", &u) - 0.45).abs() < 1e-9);
+        assert_eq!(cost_usd("unknown-model", &u), 0.0);
+    }
+}
@@ -1,10 +1,12 @@
 // backend/src/util/logging.rs
 
-use std::{env, fs, path::PathBuf, sync::OnceLock, time::SystemTime};
+use std::{fs, path::PathBuf, sync::OnceLock, time::SystemTime};
 use tracing_appender::non_blocking::WorkerGuard;
 use tracing_appender::rolling::{daily, hourly};
 use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
+use crate::config::Config;
+
 static FILE_GUARD: OnceLock<WorkerGuard> = OnceLock::new();
 
 fn default_filter() -> String {
@@ -15,21 +17,20 @@ fn ensure_dir(p: &PathBuf) {
     let _ = fs::create_dir_all(p);
 }
 
-pub fn init_tracing() {
+pub fn init_tracing(cfg: &Config) {
     let filter =
         EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_filter()));
 
-    let log_dir = env::var("LOG_DIR").unwrap_or_else(|_| "logs".to_string());
-    let roll = env::var("LOG_ROLL").unwrap_or_else(|_| "daily".to_string()); // "daily" | "hourly"
-    let file = env::var("LOG_FILE").unwrap_or_else(|_| "backend.log".to_string());
+    let roll = &cfg.log_roll; // "daily" | "hourly"
+    let file = &cfg.log_file;
 
     // warning fix: not mutable
-    let dir = PathBuf::from(&log_dir);
+    let dir = PathBuf::from(&cfg.log_dir);
     ensure_dir(&dir);
 
     let appender = match roll.as_str() {
-        "hourly" => hourly(&dir, &file),
-        _ => daily(&dir, &file),
+        "hourly" => hourly(&dir, file),
+        _ => daily(&dir, file),
     };
 
     // panggil fungsi fully-qualified
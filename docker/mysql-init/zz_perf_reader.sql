@@ -0,0 +1,6 @@
+-- User read-only untuk adapter db_perf (performance_schema + processlist).
+-- Dijalankan sekali oleh entrypoint MySQL setelah migrations.
+CREATE USER IF NOT EXISTS 'perf_reader'@'%' IDENTIFIED BY 'perfpass';
+GRANT SELECT ON performance_schema.* TO 'perf_reader'@'%';
+GRANT PROCESS ON *.* TO 'perf_reader'@'%';
+FLUSH PRIVILEGES;
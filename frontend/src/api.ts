@@ -3,8 +3,10 @@
 const BASE = (import.meta as any).env.VITE_BACKEND_BASE || ''
 
 export type Range = { date_from?: string; date_to?: string; tz?: string }
+export type Sampling = { temperature?: number; top_p?: number; max_tokens?: number; stop?: string[] }
+export type ChatOpts = Range & Sampling & { session_id?: string; structured?: boolean; profile?: string }
 
-export async function chat(text: string, range?: Range) {
+export async function chat(text: string, range?: ChatOpts) {
   const r = await fetch(`/api/chat`, {
     method: 'POST',
     headers: { 'Content-Type': 'application/json' },
@@ -15,16 +17,26 @@ export async function chat(text: string, range?: Range) {
 }
 
 // SSE GET (lebih simpel untuk EventSource)
-export function streamChatGet(params: { text: string; tz?: string; date_from?: string; date_to?: string }) {
+export function streamChatGet(params: { text: string; tz?: string; date_from?: string; date_to?: string; session_id?: string; structured?: boolean; profile?: string }) {
   const q = new URLSearchParams()
   q.set('text', params.text)
   if (params.tz) q.set('tz', params.tz)
   if (params.date_from) q.set('date_from', params.date_from)
   if (params.date_to) q.set('date_to', params.date_to)
+  if (params.session_id) q.set('session_id', params.session_id)
+  if (params.structured) q.set('structured', 'true')
+  if (params.profile) q.set('profile', params.profile)
   const url = `${BASE}/api/chat/stream?${q.toString()}`
   return new EventSource(url)
 }
 
+// Hentikan stream di server (fetch & LLM ikut dibatalkan); session_id = yang membuka stream
+export async function cancelChat(streamId: string, sessionId?: string) {
+  const q = sessionId ? `?session_id=${encodeURIComponent(sessionId)}` : ''
+  const r = await fetch(`${BASE}/api/chat/${encodeURIComponent(streamId)}/cancel${q}`, { method: 'POST' })
+  return r.ok
+}
+
 // Optional settings endpoints (safe to call; backend may stub)
 export async function getSettings() {
   const r = await fetch('/api/settings')
@@ -39,3 +51,9 @@ export async function saveSettings(payload: any) {
   })
   return r.ok ? r.json() : {}
 }
+
+// Settings profiles per tim
+export async function listProfiles(team?: string) {
+  const r = await fetch(`/api/profiles${team ? `?team=${encodeURIComponent(team)}` : ''}`)
+  return r.ok ? r.json() : []
+}
@@ -0,0 +1,16 @@
+-- Pemakaian token & biaya per panggilan LLM (plan / answer)
+CREATE TABLE IF NOT EXISTS llm_usage (
+  id BIGINT AUTO_INCREMENT PRIMARY KEY,
+  request_id VARCHAR(128) NOT NULL,
+  chat_log_id BIGINT NULL,
+  user_id VARCHAR(64) NULL,
+  model VARCHAR(64) NOT NULL,
+  phase VARCHAR(32) NOT NULL,
+  prompt_tokens INT UNSIGNED NOT NULL DEFAULT 0,
+  completion_tokens INT UNSIGNED NOT NULL DEFAULT 0,
+  cost_usd DECIMAL(12,6) NOT NULL DEFAULT 0,
+  created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+  INDEX (request_id),
+  INDEX (created_at),
+  FOREIGN KEY (chat_log_id) REFERENCES chat_logs(id) ON DELETE SET NULL
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
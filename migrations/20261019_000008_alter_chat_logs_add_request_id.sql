@@ -0,0 +1,8 @@
+-- Hubungkan chat_logs dengan x-request-id (llm_usage, debug_traces)
+ALTER TABLE chat_logs
+ADD COLUMN request_id VARCHAR(128) NULL AFTER id,
+ADD INDEX idx_chat_logs_request_id (request_id),
+ADD INDEX idx_chat_logs_created_at (created_at);
+
+ALTER TABLE api_results
+ADD COLUMN status VARCHAR(16) NOT NULL DEFAULT 'ok' AFTER endpoint;
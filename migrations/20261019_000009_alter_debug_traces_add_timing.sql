@@ -0,0 +1,4 @@
+-- Timing per fase (ms sejak request diterima) + presisi ms untuk created_at
+ALTER TABLE debug_traces
+ADD COLUMN elapsed_ms INT UNSIGNED NULL AFTER payload,
+MODIFY created_at TIMESTAMP(3) DEFAULT CURRENT_TIMESTAMP(3);
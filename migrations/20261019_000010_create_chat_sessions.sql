@@ -0,0 +1,15 @@
+-- Sesi percakapan multi-turn; giliran disimpan di chat_logs.session_id
+CREATE TABLE IF NOT EXISTS chat_sessions (
+  id VARCHAR(64) PRIMARY KEY,
+  user_id VARCHAR(64) NULL,
+  title VARCHAR(255) NULL,
+  created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+  updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  INDEX (user_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- Hapus session tidak menghapus audit chat_logs, hanya memutus history-nya
+ALTER TABLE chat_logs
+ADD COLUMN session_id VARCHAR(64) NULL AFTER request_id,
+ADD INDEX idx_chat_logs_session_id (session_id),
+ADD CONSTRAINT fk_chat_logs_session FOREIGN KEY (session_id) REFERENCES chat_sessions(id) ON DELETE SET NULL;
@@ -0,0 +1,21 @@
+-- Settings aktif = baris settings id=1; setiap perubahan disimpan utuh di settings_revisions.
+-- Baris id=1 tidak di-seed: sebelum admin menyimpan versi pertama, model & prompt ikut config.
+ALTER TABLE settings
+ADD COLUMN streaming TINYINT(1) NOT NULL DEFAULT 1 AFTER top_p,
+ADD COLUMN version INT NOT NULL DEFAULT 0 AFTER streaming,
+ADD COLUMN updated_by VARCHAR(64) NULL AFTER version;
+
+CREATE TABLE IF NOT EXISTS settings_revisions (
+  id BIGINT AUTO_INCREMENT PRIMARY KEY,
+  version INT NOT NULL,
+  system_prompt TEXT NOT NULL,
+  response_prompt TEXT NULL,
+  model VARCHAR(64) NOT NULL,
+  temperature DECIMAL(3,2) NOT NULL,
+  top_p DECIMAL(3,2) NOT NULL,
+  streaming TINYINT(1) NOT NULL DEFAULT 1,
+  author VARCHAR(64) NULL,
+  note VARCHAR(255) NULL,
+  created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+  UNIQUE KEY uq_settings_revisions_version (version)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,20 @@
+-- Profil settings per tim; kolom NULL = ikut settings global
+CREATE TABLE IF NOT EXISTS settings_profiles (
+  id BIGINT AUTO_INCREMENT PRIMARY KEY,
+  name VARCHAR(64) NOT NULL,
+  team VARCHAR(64) NULL,
+  provider VARCHAR(32) NOT NULL DEFAULT 'openai',
+  model VARCHAR(64) NULL,
+  temperature DECIMAL(3,2) NULL,
+  top_p DECIMAL(3,2) NULL,
+  system_prompt TEXT NULL,
+  response_prompt TEXT NULL,
+  allowed_endpoints JSON NULL,
+  created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+  updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+  UNIQUE KEY uq_settings_profiles_name (name)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- Profil default per user (dipakai kalau request tidak memilih profil)
+ALTER TABLE users
+ADD COLUMN settings_profile VARCHAR(64) NULL AFTER role;
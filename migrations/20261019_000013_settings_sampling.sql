@@ -0,0 +1,10 @@
+-- Parameter sampling langkah answer + batas override per-request (JSON SamplingBounds)
+ALTER TABLE settings
+ADD COLUMN max_tokens INT NULL AFTER top_p,
+ADD COLUMN stop JSON NULL AFTER max_tokens,
+ADD COLUMN bounds JSON NULL AFTER stop;
+
+ALTER TABLE settings_revisions
+ADD COLUMN max_tokens INT NULL AFTER top_p,
+ADD COLUMN stop JSON NULL AFTER max_tokens,
+ADD COLUMN bounds JSON NULL AFTER stop;
@@ -0,0 +1,27 @@
+-- Incident lokal (backend adapter incident_metrics = "local"); waktu dalam UTC
+CREATE TABLE IF NOT EXISTS incidents (
+  id VARCHAR(64) PRIMARY KEY,
+  title VARCHAR(255) NOT NULL,
+  service VARCHAR(64) NOT NULL,
+  severity VARCHAR(16) NOT NULL DEFAULT 'SEV3',
+  status VARCHAR(16) NOT NULL DEFAULT 'open',    -- open | acknowledged | resolved
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  acknowledged_at TIMESTAMP NULL,
+  resolved_at TIMESTAMP NULL,
+  KEY idx_incidents_created (created_at),
+  KEY idx_incidents_service (service, created_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+-- Riwayat deploy untuk korelasi incident ↔ release/rollback
+CREATE TABLE IF NOT EXISTS releases (
+  id BIGINT AUTO_INCREMENT PRIMARY KEY,
+  service VARCHAR(64) NOT NULL,
+  version VARCHAR(64) NOT NULL,
+  environment VARCHAR(32) NOT NULL DEFAULT 'production',
+  started_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  finished_at TIMESTAMP NULL,
+  status VARCHAR(16) NOT NULL DEFAULT 'success', -- success | failed | rolled_back
+  rollback_of VARCHAR(64) NULL,                  -- diisi bila deploy ini rollback dari versi tsb
+  KEY idx_releases_started (environment, started_at),
+  KEY idx_releases_service (service, started_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
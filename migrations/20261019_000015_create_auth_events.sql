@@ -0,0 +1,13 @@
+-- Event autentikasi (backend adapter security_auth = "events"); waktu dalam UTC
+CREATE TABLE IF NOT EXISTS auth_events (
+  id BIGINT AUTO_INCREMENT PRIMARY KEY,
+  occurred_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP(3),
+  service VARCHAR(64) NOT NULL DEFAULT 'api-gateway',
+  username VARCHAR(255) NULL,
+  ip VARCHAR(45) NULL,
+  outcome VARCHAR(16) NOT NULL,               -- success | failure | lockout
+  reason VARCHAR(64) NULL,                    -- bad_password | unknown_user | mfa_failed ...
+  user_agent VARCHAR(255) NULL,
+  KEY idx_auth_events_time (occurred_at),
+  KEY idx_auth_events_service (service, occurred_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
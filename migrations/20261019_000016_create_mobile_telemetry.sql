@@ -0,0 +1,32 @@
+-- Ingest telemetry mobile (backend adapter mobile_telemetry = "local"); waktu dalam UTC.
+-- Diisi dari export SDK mobile: satu baris per sesi, satu baris per crash/ANR.
+CREATE TABLE IF NOT EXISTS mobile_sessions (
+  id BIGINT AUTO_INCREMENT PRIMARY KEY,
+  session_id VARCHAR(64) NOT NULL,
+  started_at TIMESTAMP(3) NOT NULL,
+  platform VARCHAR(16) NOT NULL,                -- android | ios
+  app_version VARCHAR(32) NOT NULL,
+  os_version VARCHAR(32) NOT NULL,
+  device_model VARCHAR(64) NULL,
+  duration_secs INT NULL,
+  crashed TINYINT(1) NOT NULL DEFAULT 0,        -- sesi berakhir karena crash
+  anr TINYINT(1) NOT NULL DEFAULT 0,            -- sesi mengalami ANR / app hang
+  UNIQUE KEY uq_mobile_sessions_session (session_id),
+  KEY idx_mobile_sessions_time (started_at),
+  KEY idx_mobile_sessions_version (platform, app_version, started_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS mobile_crashes (
+  id BIGINT AUTO_INCREMENT PRIMARY KEY,
+  session_id VARCHAR(64) NULL,
+  occurred_at TIMESTAMP(3) NOT NULL,
+  platform VARCHAR(16) NOT NULL,
+  app_version VARCHAR(32) NOT NULL,
+  os_version VARCHAR(32) NOT NULL,
+  kind VARCHAR(8) NOT NULL DEFAULT 'crash',     -- crash | anr
+  signature VARCHAR(255) NOT NULL,              -- grouping key dari SDK (exception + frame teratas)
+  exception_type VARCHAR(128) NULL,
+  message TEXT NULL,
+  KEY idx_mobile_crashes_time (occurred_at),
+  KEY idx_mobile_crashes_signature (signature, occurred_at)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,6 @@
+-- Temperature langkah planner (routing JSON); default 0 = deterministik
+ALTER TABLE settings
+ADD COLUMN planner_temperature DECIMAL(3,2) NOT NULL DEFAULT 0.00 AFTER top_p;
+
+ALTER TABLE settings_revisions
+ADD COLUMN planner_temperature DECIMAL(3,2) NOT NULL DEFAULT 0.00 AFTER top_p;
@@ -49,7 +49,8 @@ log "jq: $([[ $have_jq -eq 1 ]] && echo 'found' || echo 'not found (output not p
 # 1) Health
 get "$BASE_URL/health" "health"
 
-# 2) 10 dummy endpoints
+# 2) 10 adapter endpoints (+ katalog adapter aktif)
+get "$BASE_URL/api/adapters" "adapters"
 get "$BASE_URL/api/gitlab-ci?date_from=$DATE_FROM&date_to=$DATE_TO&tz=$TZ" "gitlab-ci"
 get "$BASE_URL/api/runtime-logs?tz=$TZ" "runtime-logs"
 get "$BASE_URL/api/cloud-mon?date_from=$DATE_FROM&date_to=$DATE_TO&tz=$TZ" "cloud-mon"
@@ -73,10 +74,18 @@ post "$BASE_URL/api/settings" \
   "top_p":0.9
 }' \
 "settings POST"
+get  "$BASE_URL/api/settings/history?limit=5" "settings history"
+get  "$BASE_URL/api/profiles" "settings profiles"
 
 # 4) Joiner test
 get "$BASE_URL/api/test-join?date_from=$DATE_FROM&date_to=$DATE_TO&tz=$TZ" "test-join"
 
+# 5) Token usage aggregate
+get "$BASE_URL/api/usage?date_from=${DATE_FROM:0:10}&date_to=${DATE_TO:0:10}" "usage"
+
+# 6) Chat history
+get "$BASE_URL/api/chats?limit=5" "chats list"
+
 # Summary
 echo
 log "Summary:"